@@ -0,0 +1,217 @@
+use crate::hash::Hash;
+use crate::md5_error::Md5Error;
+use crate::{Md5Hasher, DEFAULT_READ_BUFFER_SIZE};
+
+use std::fs::File;
+use std::io::Read;
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::sync::{Mutex, PoisonError};
+use std::thread;
+
+// Maximum number of workers per CPU, which leaves room for workers blocked on I/O.
+const MAX_THREADS_PER_CPU: usize = 4;
+
+// Hashes every input in a pool of `threads` workers that take the inputs one by one, so only
+// one input and one read buffer per worker are alive at the same time.
+fn run<I, F>(inputs: I, threads: usize, hash: F) -> Vec<Result<Hash, Md5Error>>
+where
+    I: IntoIterator,
+    I::IntoIter: Send,
+    I::Item: Send,
+    F: Fn(I::Item, &mut [u8]) -> Result<Hash, Md5Error> + Sync,
+{
+    let parallelism = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let threads = match threads {
+        0 => parallelism,
+        threads => threads.min(parallelism.saturating_mul(MAX_THREADS_PER_CPU)),
+    };
+    let inputs = inputs.into_iter();
+    // No more workers than inputs are spawned, as the rest would have nothing to hash.
+    let threads = inputs
+        .size_hint()
+        .1
+        .map_or(threads, |length| threads.min(length));
+    let inputs = Mutex::new(inputs.enumerate());
+    let mut indexed: Vec<(usize, Result<Hash, Md5Error>)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut buffer = vec![0; DEFAULT_READ_BUFFER_SIZE];
+                    let mut results = Vec::new();
+                    loop {
+                        let next = inputs.lock().unwrap_or_else(PoisonError::into_inner).next();
+                        let Some((index, input)) = next else {
+                            break;
+                        };
+                        results.push((index, hash(input, &mut buffer)));
+                    }
+                    results
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| match worker.join() {
+                Ok(results) => results,
+                Err(payload) => std::panic::resume_unwind(payload),
+            })
+            .collect()
+    });
+    indexed.sort_unstable_by_key(|(index, _)| *index);
+    indexed.into_iter().map(|(_, result)| result).collect()
+}
+
+impl Md5Hasher {
+    /// Computes the hash of every reader in `inputs` using a pool of `threads` workers,
+    /// returning the results in the same order as the `inputs`.
+    ///
+    /// The `inputs` are taken lazily as the workers become idle, and each worker reuses a single
+    /// read buffer, so the memory used depends on `threads` and not on the number of inputs.
+    /// When `threads` is 0 the [available parallelism](std::thread::available_parallelism) is
+    /// used; no more than 4 workers per CPU, nor more workers than inputs when their number is
+    /// known, are used.
+    ///
+    /// An error reading an input is returned as the result of that input without affecting the
+    /// others.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use ya_md5::Md5Hasher;
+    ///
+    /// let inputs = ["hello", "world"].map(Cursor::new);
+    /// let results = Md5Hasher::hash_many(inputs, 2);
+    /// assert_eq!(format!("{}", results[0].as_ref().unwrap()), "5d41402abc4b2a76b9719d911017c592");
+    /// assert_eq!(format!("{}", results[1].as_ref().unwrap()), "7d793037a0760186574b0282f2f435e7");
+    /// ```
+    pub fn hash_many<I>(inputs: I, threads: usize) -> Vec<Result<Hash, Md5Error>>
+    where
+        I: IntoIterator,
+        I::IntoIter: Send,
+        I::Item: Read + Send,
+    {
+        run(inputs, threads, |mut input, buffer| {
+            let mut hasher = Md5Hasher::default();
+            hasher.update_reader_with_buffer(&mut input, buffer)?;
+            Ok(hasher.finalize())
+        })
+    }
+
+    /// Computes the hash of every file in `paths` using a pool of `threads` workers, returning
+    /// the results in the same order as the `paths`.
+    ///
+    /// Works like [Md5Hasher::hash_many], each file is opened by the worker that hashes it; an
+    /// error opening a file is returned as a [Md5Error::IOError].
+    pub fn hash_files<I>(paths: I, threads: usize) -> Vec<Result<Hash, Md5Error>>
+    where
+        I: IntoIterator,
+        I::IntoIter: Send,
+        I::Item: AsRef<Path> + Send,
+    {
+        run(paths, threads, |path, buffer| {
+            let mut file = File::open(path)?;
+            let mut hasher = Md5Hasher::default();
+            hasher.update_reader_with_buffer(&mut file, buffer)?;
+            Ok(hasher.finalize())
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Md5Error, Md5Hasher};
+    use rstest::rstest;
+    use std::io::{Cursor, Read};
+
+    #[ctor::ctor]
+    fn init() {
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    struct FailingReader;
+
+    impl Read for FailingReader {
+        fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::ErrorKind::Other.into())
+        }
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(1)]
+    #[case(3)]
+    #[case(64)]
+    fn test_hash_many(#[case] threads: usize) {
+        let inputs: Vec<String> = (0..200).map(|index| "a".repeat(index)).collect();
+        let results = Md5Hasher::hash_many(inputs.iter().map(Cursor::new), threads);
+        assert_eq!(results.len(), inputs.len());
+        for (input, result) in inputs.iter().zip(results) {
+            assert_eq!(result.unwrap(), Md5Hasher::hash_str(input));
+        }
+    }
+
+    #[rstest]
+    fn test_hash_many_more_threads_than_inputs() {
+        // The number of inputs is unknown, so only the CPUs bound the workers.
+        let mut input = Some(Cursor::new("abc"));
+        let inputs = std::iter::from_fn(move || input.take());
+        let results = Md5Hasher::hash_many(inputs, usize::MAX);
+        assert_eq!(
+            results[0].as_ref().unwrap(),
+            &"900150983cd24fb0d6963f7d28e17f72"
+        );
+        let results = Md5Hasher::hash_many(["abc"].map(Cursor::new), usize::MAX);
+        assert_eq!(
+            results[0].as_ref().unwrap(),
+            &"900150983cd24fb0d6963f7d28e17f72"
+        );
+        assert!(Md5Hasher::hash_many(Vec::<Cursor<&str>>::new(), usize::MAX).is_empty());
+    }
+
+    #[rstest]
+    fn test_hash_many_error() {
+        let inputs: Vec<Box<dyn Read + Send>> = vec![
+            Box::new(Cursor::new("abc")),
+            Box::new(FailingReader),
+            Box::new(Cursor::new("")),
+        ];
+        let results = Md5Hasher::hash_many(inputs, 2);
+        assert_eq!(
+            results[0].as_ref().unwrap(),
+            &"900150983cd24fb0d6963f7d28e17f72"
+        );
+        assert!(matches!(
+            results[1],
+            Err(Md5Error::ReadError { bytes_read: 0, .. })
+        ));
+        assert_eq!(
+            results[2].as_ref().unwrap(),
+            &"d41d8cd98f00b204e9800998ecf8427e"
+        );
+    }
+
+    #[rstest]
+    fn test_hash_files() -> Result<(), Md5Error> {
+        let directory = tempfile::tempdir()?;
+        let mut paths = Vec::new();
+        for index in 0..20 {
+            let path = directory.path().join(format!("{}.txt", index));
+            std::fs::write(&path, "a".repeat(index * 10))?;
+            paths.push(path);
+        }
+        paths.insert(5, directory.path().join("missing.txt"));
+        let results = Md5Hasher::hash_files(&paths, 4);
+        assert!(matches!(results[5], Err(Md5Error::IOError(_))));
+        for (path, result) in paths
+            .iter()
+            .zip(results.iter())
+            .filter(|(path, _)| path.exists())
+        {
+            let expected = Md5Hasher::hash_vec(&std::fs::read(path)?);
+            assert_eq!(result.as_ref().unwrap(), &expected);
+        }
+        Ok(())
+    }
+}
@@ -1,104 +1,366 @@
 use crate::chunk::{Chunk, CHUNK_SIZE_BYTES};
+use crate::compression_function::{CompressionFunction, Endianness};
 use crate::conversions::u64_to_u8;
 use crate::hash::Hash;
 use crate::hash_compute_state::HashComputeState;
+use core::ops::Range;
+
+#[cfg(feature = "std")]
+use crate::md5_error::Md5Error;
+#[cfg(feature = "std")]
+use crate::{read_chunks, DEFAULT_READ_BUFFER_SIZE};
+#[cfg(feature = "std")]
+use std::io::Read;
 
 const INITIAL_BIT_SIZE_BYTES: usize = 1;
 const INITIAL_BIT: u8 = 0x80; // 1 in big endian.
 const LENGTH_SIZE_BYTES: usize = 8; // 64 / 8
-const ZERO_PADDING_MAX_SIZE_BYTES: usize =
-    CHUNK_SIZE_BYTES - LENGTH_SIZE_BYTES - INITIAL_BIT_SIZE_BYTES;
-const CHUNK_LENGTH: u64 = CHUNK_SIZE_BYTES as u64 * 8;
+pub const CHUNK_LENGTH: u64 = CHUNK_SIZE_BYTES as u64 * 8;
 
-pub struct ChunkProcessor {
-    buffer: Vec<u8>,
-    state: HashComputeState,
+/// Splits a message in blocks, pads it and feeds them to the [CompressionFunction] `F`, MD5 by
+/// default.
+///
+/// A processor can start from any state, like MD5 with custom initial values, and returns to it
+/// when reset.
+///
+/// # Examples
+///
+/// ```
+/// use ya_md5::{ChunkProcessor, HashComputeState, Md5Hasher};
+///
+/// let mut processor = ChunkProcessor::<HashComputeState>::default();
+/// processor.update("hello world");
+/// assert_eq!(processor.finalize(), Md5Hasher::hash_str("hello world"));
+///
+/// let initial = HashComputeState::from_words([0x01234567, 0x89abcdef, 0xfedcba98, 0x76543210]);
+/// let mut processor = ChunkProcessor::new(initial);
+/// processor.update("hello world");
+/// assert_ne!(processor.finalize(), Md5Hasher::hash_str("hello world"));
+/// ```
+#[derive(Clone)]
+pub struct ChunkProcessor<F: CompressionFunction = HashComputeState> {
+    buffer: F::Block,
+    buffer_length: usize,
+    // Number of bits of the last byte of the buffer that belong to the message, 0 if all of them.
+    partial_bits: u8,
+    initial: F,
+    state: F,
     size: u64,
 }
 
-impl Default for ChunkProcessor {
+impl<F: CompressionFunction + Default> Default for ChunkProcessor<F> {
     fn default() -> Self {
-        ChunkProcessor {
-            buffer: Vec::with_capacity(CHUNK_SIZE_BYTES),
-            state: HashComputeState::default(),
-            size: 0,
-        }
+        Self::new(F::default())
     }
 }
 
-fn write_length(chunk: &mut Chunk, size: u64) {
+pub(crate) const fn write_length(block: &mut [u8], size: u64, endianness: Endianness) {
     let mut length: [u8; 8] = [0; 8];
-    u64_to_u8(&(size & u64::MAX), &mut length);
-    for x in 0..8 {
-        let chunk_position = ZERO_PADDING_MAX_SIZE_BYTES + x + 1;
-        let length_position = x;
-        chunk[chunk_position] = length[length_position];
+    match endianness {
+        Endianness::Little => u64_to_u8(&size, &mut length),
+        Endianness::Big => length = size.to_be_bytes(),
+    }
+    let offset = block.len() - LENGTH_SIZE_BYTES;
+    let mut index = 0;
+    while index < LENGTH_SIZE_BYTES {
+        block[offset + index] = length[index];
+        index += 1;
+    }
+}
+
+// Pads the last `buffer_length` bytes of a message, stored at the start of the `block`, up to the
+// length, returning whether the length doesn't fit and needs another block; if `partial_bits` is
+// not 0 only those bits of the last byte belong to the message, and the initial bit of the padding
+// goes right after them.
+pub(crate) const fn pad_block(block: &mut [u8], buffer_length: usize, partial_bits: u8) -> bool {
+    let marker = if partial_bits == 0 {
+        buffer_length
+    } else {
+        buffer_length - 1
+    };
+    block[marker] = (block[marker] & !(0xff >> partial_bits)) | (INITIAL_BIT >> partial_bits);
+    let mut index = marker + INITIAL_BIT_SIZE_BYTES;
+    while index < block.len() {
+        block[index] = 0;
+        index += 1;
+    }
+    marker + INITIAL_BIT_SIZE_BYTES > block.len() - LENGTH_SIZE_BYTES
+}
+
+/// Computes the hash of the `data` in const context, with the same padding as
+/// [ChunkProcessor::finalize].
+pub const fn hash_const(data: &[u8]) -> Hash {
+    let mut state = HashComputeState::new();
+    let mut chunk: Chunk = [0; CHUNK_SIZE_BYTES];
+    let mut offset = 0;
+    while offset < data.len() {
+        chunk[offset % CHUNK_SIZE_BYTES] = data[offset];
+        offset += 1;
+        if offset.is_multiple_of(CHUNK_SIZE_BYTES) {
+            state = state.process_chunk(&chunk);
+        }
+    }
+    let size = (data.len() as u64).wrapping_mul(8);
+    if pad_block(&mut chunk, data.len() % CHUNK_SIZE_BYTES, 0) {
+        state = state.process_chunk(&chunk);
+        chunk = [0; CHUNK_SIZE_BYTES];
     }
+    write_length(&mut chunk, size, Endianness::Little);
+    Hash::from_bytes(state.process_chunk(&chunk).to_raw())
 }
 
-impl ChunkProcessor {
+/// Returns the padding that [CompressionFunction] `F` appends to a message of `size` bits: the
+/// initial bit, the zeros and the length, so the padded message fills a whole number of blocks.
+///
+/// The padding is stored in the returned blocks at the returned range, after the place of the
+/// last bytes of the message.
+pub(crate) fn padding<F: CompressionFunction>(size: u64) -> ([F::Block; 2], Range<usize>) {
+    let buffer_length = (size / 8 % F::BLOCK_SIZE as u64) as usize;
+    let mut blocks = [F::EMPTY_BLOCK; 2];
+    let end = if pad_block(blocks[0].as_mut(), buffer_length, 0) {
+        write_length(blocks[1].as_mut(), size, F::LENGTH_ENDIANNESS);
+        F::BLOCK_SIZE * 2
+    } else {
+        write_length(blocks[0].as_mut(), size, F::LENGTH_ENDIANNESS);
+        F::BLOCK_SIZE
+    };
+    (blocks, buffer_length..end)
+}
+
+impl<F: CompressionFunction> ChunkProcessor<F> {
+    /// Creates a processor that starts from the `initial` state.
+    ///
+    /// Fails to compile if the block of `F` can't hold the initial bit and the length.
+    ///
+    /// ```compile_fail
+    /// use ya_md5::{ChunkProcessor, CompressionFunction, Endianness};
+    ///
+    /// #[derive(Copy, Clone)]
+    /// struct Short;
+    ///
+    /// impl CompressionFunction for Short {
+    ///     type Block = [u8; 8];
+    ///     type Output = ();
+    ///     const BLOCK_SIZE: usize = 8;
+    ///     const EMPTY_BLOCK: [u8; 8] = [0; 8];
+    ///     const LENGTH_ENDIANNESS: Endianness = Endianness::Little;
+    ///
+    ///     fn compress(self, _block: &[u8; 8]) -> Self {
+    ///         self
+    ///     }
+    ///
+    ///     fn output(self) {}
+    /// }
+    ///
+    /// let processor = ChunkProcessor::new(Short);
+    /// ```
+    pub fn new(initial: F) -> Self {
+        const {
+            assert!(
+                F::BLOCK_SIZE >= INITIAL_BIT_SIZE_BYTES + LENGTH_SIZE_BYTES,
+                "The block must hold the initial bit and the length"
+            )
+        };
+        debug_assert_eq!(F::EMPTY_BLOCK.as_ref().len(), F::BLOCK_SIZE);
+        ChunkProcessor {
+            buffer: F::EMPTY_BLOCK,
+            buffer_length: 0,
+            partial_bits: 0,
+            initial,
+            state: initial,
+            size: 0,
+        }
+    }
+
+    /// Creates a processor from its parts; the `buffer` must be shorter than a chunk unless its
+    /// last byte is partial, and `partial_bits` lower than 8.
+    pub(crate) fn from_parts(state: F, buffer: &[u8], partial_bits: u8, size: u64) -> Self
+    where
+        F: Default,
+    {
+        debug_assert!(buffer.len() <= F::BLOCK_SIZE && partial_bits < 8);
+        let mut processor = ChunkProcessor {
+            state,
+            size,
+            buffer_length: buffer.len(),
+            partial_bits,
+            ..Default::default()
+        };
+        processor.buffer.as_mut()[..buffer.len()].copy_from_slice(buffer);
+        processor
+    }
+
+    #[cfg(any(feature = "alloc", test))]
+    pub(crate) fn state(&self) -> F {
+        self.state
+    }
+
+    #[cfg(any(feature = "alloc", test))]
+    pub(crate) fn buffer(&self) -> &[u8] {
+        &self.buffer.as_ref()[..self.buffer_length]
+    }
+
+    #[cfg(any(feature = "alloc", test))]
+    pub(crate) fn partial_bits(&self) -> u8 {
+        self.partial_bits
+    }
+
+    #[cfg(any(feature = "alloc", test))]
+    pub(crate) fn size(&self) -> u64 {
+        self.size
+    }
+
+    fn process_block(&mut self, block: &F::Block) {
+        self.state = self.state.compress(block);
+        self.size = self.size.wrapping_add(F::BLOCK_SIZE as u64 * 8);
+    }
+
+    /// Process the `data` as part of the message.
     pub fn update(&mut self, data: impl AsRef<[u8]>) {
         let mut data = data.as_ref();
-        if !self.buffer.is_empty() {
-            let size = data.len() + self.buffer.len();
-            if size < CHUNK_SIZE_BYTES {
-                self.buffer.extend_from_slice(data);
+        if self.partial_bits != 0 {
+            // Every byte is split between the partial byte and the next one.
+            for byte in data {
+                self.update_partial_byte(*byte, 8);
+            }
+            return;
+        }
+        if self.buffer_length > 0 {
+            let available = F::BLOCK_SIZE - self.buffer_length;
+            if data.len() < available {
+                self.buffer.as_mut()[self.buffer_length..self.buffer_length + data.len()]
+                    .copy_from_slice(data);
+                self.buffer_length += data.len();
                 return;
             }
-            let (for_chunk, extra) = data.split_at(CHUNK_SIZE_BYTES - self.buffer.len());
+            let (for_block, extra) = data.split_at(available);
             data = extra;
-            self.buffer.extend_from_slice(for_chunk);
-            log::debug!(
-                "Buffer filled: length={0}, content={1:?}",
-                self.buffer.len(),
-                self.buffer
-            );
-            let chunk = &Chunk::try_from(self.buffer.drain(..).as_slice()).unwrap();
-            self.state = self.state.process_chunk(chunk);
-            self.size += CHUNK_LENGTH;
-        }
-        let chunks_iter = data.chunks_exact(CHUNK_SIZE_BYTES);
-        self.buffer.extend_from_slice(chunks_iter.remainder());
-        chunks_iter.for_each(|raw_chunk| {
-            let chunk = &Chunk::try_from(raw_chunk).unwrap();
-            self.state = self.state.process_chunk(chunk);
-            self.size += CHUNK_LENGTH;
-        });
-    }
-
-    pub fn finalize(mut self) -> Hash {
-        let buffer_length = self.buffer.len();
-        let size = self.size + (buffer_length as u64 * 8);
-        self.buffer.push(INITIAL_BIT);
-        self.buffer
-            .append(&mut vec![0_u8; CHUNK_SIZE_BYTES - buffer_length - 1]);
-        if buffer_length > ZERO_PADDING_MAX_SIZE_BYTES {
-            log::debug!(
-                "Buffer cannot hold padding: length={0}, content={1:?}",
-                self.buffer.len(),
-                self.buffer
-            );
-            let chunk = &Chunk::try_from(self.buffer.as_slice()).unwrap();
-            self.state = self.state.process_chunk(chunk);
-            self.buffer.clear();
-            self.buffer.append(&mut vec![0_u8; CHUNK_SIZE_BYTES]);
+            self.buffer.as_mut()[self.buffer_length..].copy_from_slice(for_block);
+            log::debug!("Buffer filled: content={0:?}", self.buffer.as_ref());
+            let block = self.buffer;
+            self.process_block(&block);
+            self.buffer_length = 0;
+        }
+        let mut blocks_iter = data.chunks_exact(F::BLOCK_SIZE);
+        for raw_block in &mut blocks_iter {
+            let mut block = F::EMPTY_BLOCK;
+            block.as_mut().copy_from_slice(raw_block);
+            self.process_block(&block);
+        }
+        let remainder = blocks_iter.remainder();
+        self.buffer.as_mut()[..remainder.len()].copy_from_slice(remainder);
+        self.buffer_length = remainder.len();
+    }
+
+    /// Processes the first `bit_length` bits of the `data`, taking the bits of each byte from the
+    /// most significant one.
+    ///
+    /// # Panics
+    ///
+    /// If `bit_length` is greater than the number of bits in `data`.
+    pub fn update_bits(&mut self, data: &[u8], bit_length: usize) {
+        assert!(
+            bit_length <= data.len() * 8,
+            "The bit length {} is greater than the {} bits of the data",
+            bit_length,
+            data.len() * 8
+        );
+        let whole_bytes = bit_length / 8;
+        self.update(&data[..whole_bytes]);
+        let bits = (bit_length % 8) as u8;
+        if bits != 0 {
+            self.update_partial_byte(data[whole_bytes], bits);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    /// Process all the data that can be readed from the `input` as part of the message, returning
+    /// the number of bytes readed.
+    ///
+    /// # Errors
+    ///
+    /// Works like [Md5Hasher::update_reader](crate::Md5Hasher::update_reader), including
+    /// resuming after an error.
+    pub fn update_reader<R: Read + ?Sized>(&mut self, input: &mut R) -> Result<u64, Md5Error> {
+        read_chunks(input, &mut [0; DEFAULT_READ_BUFFER_SIZE], |data| {
+            self.update(data)
+        })
+    }
+
+    // Appends the `bits` most significant bits of the `byte`.
+    fn update_partial_byte(&mut self, byte: u8, bits: u8) {
+        let byte = byte & !(0xff_u8.checked_shr(bits as u32).unwrap_or(0));
+        if self.partial_bits == 0 {
+            self.buffer.as_mut()[self.buffer_length] = byte;
+            self.buffer_length += 1;
+            self.partial_bits = bits % 8;
+            self.flush_full_buffer();
+            return;
+        }
+        let free_bits = 8 - self.partial_bits;
+        self.buffer.as_mut()[self.buffer_length - 1] |= byte >> self.partial_bits;
+        if bits < free_bits {
+            self.partial_bits += bits;
+            return;
+        }
+        self.partial_bits = 0;
+        self.flush_full_buffer();
+        if bits > free_bits {
+            self.update_partial_byte(byte << free_bits, bits - free_bits);
+        }
+    }
+
+    fn flush_full_buffer(&mut self) {
+        if self.buffer_length == F::BLOCK_SIZE && self.partial_bits == 0 {
+            let block = self.buffer;
+            self.process_block(&block);
+            self.buffer_length = 0;
         }
+    }
+
+    /// Computes the digest of the message, consuming the instance in the process.
+    pub fn finalize(mut self) -> F::Output {
+        self.finalize_reset()
+    }
+
+    /// Discards the data processed so far, returning the instance to its initial state.
+    pub fn reset(&mut self) {
+        self.buffer_length = 0;
+        self.partial_bits = 0;
+        self.state = self.initial;
+        self.size = 0;
+    }
+
+    /// Computes the digest of the message and resets the instance to its initial state.
+    pub fn finalize_reset(&mut self) -> F::Output {
+        let mut buffer_bits = self.buffer_length as u64 * 8;
+        if self.partial_bits != 0 {
+            buffer_bits -= 8 - self.partial_bits as u64;
+        }
+        let size = self.size.wrapping_add(buffer_bits);
         log::debug!(
-            "Buffer remaining: length={0}, content={1:?}",
-            self.buffer.len(),
-            self.buffer
+            "Buffer remaining: content={0:?}, partial bits={1}",
+            &self.buffer.as_ref()[..self.buffer_length],
+            self.partial_bits
         );
-        let chunk = &mut Chunk::try_from(self.buffer.as_slice()).unwrap();
-        chunk[buffer_length] = INITIAL_BIT;
-        write_length(chunk, size);
-        self.state = self.state.process_chunk(chunk);
-        Hash::from(self.state.to_raw())
+        let mut block = self.buffer;
+        if pad_block(block.as_mut(), self.buffer_length, self.partial_bits) {
+            self.state = self.state.compress(&block);
+            block = F::EMPTY_BLOCK;
+        }
+        write_length(block.as_mut(), size, F::LENGTH_ENDIANNESS);
+        let digest = self.state.compress(&block).output();
+        self.reset();
+        digest
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::ChunkProcessor;
+    use super::{
+        hash_const, padding, ChunkProcessor, CompressionFunction, Endianness, Hash,
+        HashComputeState, CHUNK_SIZE_BYTES,
+    };
     use rstest::rstest;
 
     #[ctor::ctor]
@@ -149,12 +411,13 @@ mod test {
     )]
     fn test_hash_rfc_examples(#[case] data: &str, #[case] expected: &str) {
         let digest = {
-            let mut processor = ChunkProcessor::default();
+            let mut processor = ChunkProcessor::<HashComputeState>::default();
             processor.update(data.as_bytes());
             processor.finalize()
         };
         let result = format!("{}", digest);
         assert_eq!(result, expected);
+        assert_eq!(hash_const(data.as_bytes()), digest);
     }
 
     #[rustfmt::skip]
@@ -164,7 +427,7 @@ mod test {
     #[case("12345678901234567890123456789012345678901234567890123456789012", "123", "353e895e9d0a7bb2151c1f0120f036d2")]
     fn test_buffer_filled(#[case] left: &str, #[case] right: &str, #[case] expected: &str) {
         let digest = {
-            let mut processor = ChunkProcessor::default();
+            let mut processor = ChunkProcessor::<HashComputeState>::default();
             processor.update(left.as_bytes());
             processor.update(right.as_bytes());
             processor.finalize()
@@ -172,4 +435,186 @@ mod test {
         let result = format!("{}", digest);
         assert_eq!(result, expected);
     }
+
+    #[rstest]
+    #[case(0)]
+    #[case(3)]
+    #[case(55)]
+    #[case(56)]
+    #[case(63)]
+    #[case(64)]
+    #[case(130)]
+    fn test_padding(#[case] length: usize) {
+        let data = vec![b'a'; length];
+        let (blocks, range) = padding::<HashComputeState>(length as u64 * 8);
+        let padding = &blocks.as_flattened()[range];
+        assert_eq!((length + padding.len()) % CHUNK_SIZE_BYTES, 0);
+        assert!(padding.len() <= CHUNK_SIZE_BYTES + 8);
+        // Processing the padded message leaves the same state as finalizing the message.
+        let padded = {
+            let mut processor = ChunkProcessor::<HashComputeState>::default();
+            processor.update(&data);
+            processor.update(padding);
+            assert!(processor.buffer().is_empty());
+            processor.state.to_raw()
+        };
+        let digest = {
+            let mut processor = ChunkProcessor::<HashComputeState>::default();
+            processor.update(&data);
+            processor.finalize()
+        };
+        assert_eq!(digest, padded);
+    }
+
+    #[rstest]
+    #[should_panic(expected = "The bit length 9 is greater than the 8 bits of the data")]
+    fn test_update_bits_too_long() {
+        ChunkProcessor::<HashComputeState>::default().update_bits(&[0], 9);
+    }
+
+    fn bits_data() -> Vec<u8> {
+        (0..80_u32).map(|index| (index * 37 + 11) as u8).collect()
+    }
+
+    // NESSIE Set 2 vectors: the MD5 of a message of `bit_length` zero bits.
+    #[rstest]
+    #[case(0, "d41d8cd98f00b204e9800998ecf8427e")]
+    #[case(1, "1da635b1430f171c657206fd69fee0e8")]
+    #[case(2, "8736df18e5ec9050b84b10d74e3dd636")]
+    #[case(7, "d35652f6b84f276b349acbf6e653b3c0")]
+    #[case(8, "93b885adfe0da089cdf634904fd59f71")]
+    #[case(9, "46d61c8735b6fdbe961a42198e9b3739")]
+    #[case(15, "0bda3399dcc79f1bd29d9e1d67722a78")]
+    #[case(447, "8ca4325aa9b1ec3624b3d1e3e5e28762")]
+    #[case(448, "e3c4dd21a9171fd39d208efa09bf7883")]
+    #[case(449, "cb71c1b32945f74209ff3da439272f83")]
+    #[case(511, "33a304d6de34a0c367b2e9d6fb181466")]
+    #[case(512, "3b5d3c7d207e37dceeedd301e35e2e58")]
+    #[case(513, "a6140b57566d956c11a4b3a0fd15ff05")]
+    #[case(1023, "3b86c5f8ff317783ca76eaa1fe2835d9")]
+    fn test_update_bits_nessie(#[case] bit_length: usize, #[case] expected: &str) {
+        let mut processor = ChunkProcessor::<HashComputeState>::default();
+        processor.update_bits(&[0; 128], bit_length);
+        assert_eq!(processor.finalize(), expected);
+    }
+
+    // Messages with bits other than zeros around the padding boundaries, computed with an
+    // independent implementation that pads the message bit by bit.
+    #[rstest]
+    #[case(1, "1da635b1430f171c657206fd69fee0e8")]
+    #[case(5, "7f13ef7fd982caec097d03883b0cb9b5")]
+    #[case(7, "cff53cc022aebd99349f96810f098c59")]
+    #[case(9, "e4b350cf919b98ae976e0bf166914af8")]
+    #[case(447, "6504df9950d12a8403a1b6b68f814ff3")]
+    #[case(448, "e23567645846677c205de80f9779081b")]
+    #[case(449, "64101dc2dc98651c93996d2f41dac79b")]
+    #[case(503, "8ee420bb9cefb532c4958f4239d32842")]
+    #[case(505, "1c8fdef6e75a304dceca87f98a9d4646")]
+    #[case(511, "e2b0f03d4b94160546cfde40e740e8e2")]
+    #[case(513, "15615cff811e161b3439cc5287a559e0")]
+    fn test_update_bits(#[case] bit_length: usize, #[case] expected: &str) {
+        let data = bits_data();
+        let mut processor = ChunkProcessor::<HashComputeState>::default();
+        processor.update_bits(&data, bit_length);
+        assert_eq!(processor.finalize(), expected);
+        // The same bits split in pieces that are not whole bytes.
+        let mut processor = ChunkProcessor::<HashComputeState>::default();
+        let mut bits = Vec::new();
+        for bit in 0..bit_length {
+            bits.push(data[bit / 8] >> (7 - bit % 8) & 1);
+        }
+        for piece in bits.chunks(13) {
+            let mut packed = [0_u8; 2];
+            for (index, bit) in piece.iter().enumerate() {
+                packed[index / 8] |= bit << (7 - index % 8);
+            }
+            processor.update_bits(&packed, piece.len());
+        }
+        assert_eq!(processor.finalize(), expected);
+    }
+
+    #[rstest]
+    fn test_finalize_reset() {
+        let mut processor = ChunkProcessor::<HashComputeState>::default();
+        for (data, expected) in [
+            ("abc", "900150983cd24fb0d6963f7d28e17f72"),
+            ("", "d41d8cd98f00b204e9800998ecf8427e"),
+            (
+                "12345678901234567890123456789012345678901234567890123456789012",
+                "a29fba1f76305e4754853afb94525918",
+            ),
+            ("a", "0cc175b9c0f1b6a831c399e269772661"),
+        ] {
+            processor.update(data.as_bytes());
+            assert_eq!(processor.finalize_reset(), expected);
+            assert!(processor.buffer().is_empty());
+        }
+    }
+
+    // Keeps the last block and the number of blocks compressed, to check the padding of other
+    // block sizes and length byte orders.
+    #[derive(Default, Copy, Clone)]
+    struct LastBlock([u8; 16], usize);
+
+    impl CompressionFunction for LastBlock {
+        type Block = [u8; 16];
+        type Output = ([u8; 16], usize);
+        const BLOCK_SIZE: usize = 16;
+        const EMPTY_BLOCK: [u8; 16] = [0; 16];
+        const LENGTH_ENDIANNESS: Endianness = Endianness::Big;
+
+        fn compress(self, block: &[u8; 16]) -> Self {
+            LastBlock(*block, self.1 + 1)
+        }
+
+        fn output(self) -> ([u8; 16], usize) {
+            (self.0, self.1)
+        }
+    }
+
+    #[rstest]
+    #[case(&[1; 3], [1, 1, 1, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 24], 1)]
+    #[case(&[1; 7], [1, 1, 1, 1, 1, 1, 1, 0x80, 0, 0, 0, 0, 0, 0, 0, 56], 1)]
+    #[case(&[1; 8], [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 64], 2)]
+    #[case(&[1; 20], [1, 1, 1, 1, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 160], 2)]
+    fn test_custom_block_size(
+        #[case] data: &[u8],
+        #[case] last_block: [u8; 16],
+        #[case] blocks: usize,
+    ) {
+        let mut processor = ChunkProcessor::<LastBlock>::default();
+        processor.update(data);
+        assert_eq!(processor.finalize(), (last_block, blocks));
+    }
+
+    #[rstest]
+    #[case(3)]
+    #[case(7)]
+    #[case(8)]
+    #[case(20)]
+    fn test_custom_block_size_padding(#[case] length: usize) {
+        let mut padded = vec![1; length];
+        let mut processor = ChunkProcessor::<LastBlock>::default();
+        processor.update(&padded);
+        let (last_block, _) = processor.finalize();
+        let (blocks, range) = padding::<LastBlock>(length as u64 * 8);
+        padded.extend_from_slice(&blocks.as_flattened()[range]);
+        assert_eq!(padded.len() % 16, 0);
+        assert_eq!(padded[padded.len() - 16..], last_block);
+    }
+
+    #[rstest]
+    fn test_custom_initial_state() {
+        let initial = HashComputeState::from_words([1, 2, 3, 4]);
+        let mut chunk = [0; CHUNK_SIZE_BYTES];
+        chunk[..3].copy_from_slice(b"abc");
+        chunk[3] = 0x80;
+        chunk[CHUNK_SIZE_BYTES - 8] = 24;
+        let expected = Hash::from(initial.process_chunk(&chunk).to_raw());
+        let mut processor = ChunkProcessor::new(initial);
+        for _ in 0..2 {
+            processor.update("abc");
+            assert_eq!(processor.finalize_reset(), expected);
+        }
+    }
 }
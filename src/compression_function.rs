@@ -0,0 +1,72 @@
+/// Byte order of the message length stored at the end of the padding.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Endianness {
+    /// Least significant byte first, like MD4 and MD5.
+    Little,
+    /// Most significant byte first, like SHA-1 and SHA-256.
+    Big,
+}
+
+/// The block compression function of a Merkle–Damgård hash, implemented by its state.
+///
+/// A [ChunkProcessor](crate::ChunkProcessor) splits the message in blocks, pads it with the
+/// initial bit, the zeros and the length in bits as a 64 bit integer, and compresses each block
+/// into the state, so a hash only needs to define how a block is compressed and how the digest is
+/// taken from the state.
+///
+/// # Examples
+///
+/// A toy hash that adds the bytes of each block:
+///
+/// ```
+/// use ya_md5::{ChunkProcessor, CompressionFunction, Endianness};
+///
+/// #[derive(Default, Copy, Clone)]
+/// struct Sum(u8);
+///
+/// impl CompressionFunction for Sum {
+///     type Block = [u8; 16];
+///     type Output = u8;
+///     const BLOCK_SIZE: usize = 16;
+///     const EMPTY_BLOCK: [u8; 16] = [0; 16];
+///     const LENGTH_ENDIANNESS: Endianness = Endianness::Big;
+///
+///     fn compress(self, block: &[u8; 16]) -> Self {
+///         Sum(block.iter().fold(self.0, |sum, byte| sum.wrapping_add(*byte)))
+///     }
+///
+///     fn output(self) -> u8 {
+///         self.0
+///     }
+/// }
+///
+/// let mut processor = ChunkProcessor::new(Sum(1));
+/// processor.update([2, 3]);
+/// // 1 + 2 + 3 + the initial bit 0x80 + the length of 16 bits.
+/// assert_eq!(processor.finalize(), 0x96);
+/// ```
+pub trait CompressionFunction: Copy {
+    /// A block of the message, whose length is [BLOCK_SIZE](Self::BLOCK_SIZE); usually `[u8; N]`.
+    type Block: AsRef<[u8]> + AsMut<[u8]> + Copy;
+
+    /// The digest computed from the state, whose length is the output size; usually `[u8; N]`.
+    type Output;
+
+    /// Number of bytes of a block.
+    ///
+    /// It must be at least the 9 bytes of the initial bit and the length, which
+    /// [ChunkProcessor::new](crate::ChunkProcessor::new) checks at compile time.
+    const BLOCK_SIZE: usize;
+
+    /// A block filled with zeros.
+    const EMPTY_BLOCK: Self::Block;
+
+    /// Byte order of the message length stored at the end of the padding.
+    const LENGTH_ENDIANNESS: Endianness;
+
+    /// Returns the state after compressing the `block`.
+    fn compress(self, block: &Self::Block) -> Self;
+
+    /// Returns the digest from the state.
+    fn output(self) -> Self::Output;
+}
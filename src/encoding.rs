@@ -0,0 +1,319 @@
+use crate::md5_error::Md5Error;
+
+use core::fmt::Display;
+
+const DIGEST_SIZE_BYTES: usize = 16;
+const PADDING: u8 = b'=';
+const MAX_ENCODED_LENGTH: usize = 32;
+const HEX_ALPHABET: &[u8; 16] = b"0123456789abcdef";
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const BASE64_URL_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// The text encodings supported to parse and format a [Hash](crate::Hash).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Encoding {
+    /// Hexadecimal, either lowercase or uppercase.
+    Hex,
+    /// Base64 with the standard alphabet of [RFC 4648], padding is optional.
+    ///
+    /// [RFC 4648]: https://www.rfc-editor.org/rfc/rfc4648#section-4
+    Base64,
+    /// Base64 with the URL and filename safe alphabet of [RFC 4648], padding is optional.
+    ///
+    /// [RFC 4648]: https://www.rfc-editor.org/rfc/rfc4648#section-5
+    Base64Url,
+    /// Base32 with the alphabet of [RFC 4648], either lowercase or uppercase, padding is
+    /// optional.
+    ///
+    /// [RFC 4648]: https://www.rfc-editor.org/rfc/rfc4648#section-6
+    Base32,
+}
+
+impl Display for Encoding {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let name = match self {
+            Encoding::Hex => "hex",
+            Encoding::Base64 => "base64",
+            Encoding::Base64Url => "base64url",
+            Encoding::Base32 => "base32",
+        };
+        formatter.write_str(name)
+    }
+}
+
+impl Encoding {
+    const fn bits_per_symbol(self) -> u32 {
+        match self {
+            Encoding::Hex => 4,
+            Encoding::Base64 | Encoding::Base64Url => 6,
+            Encoding::Base32 => 5,
+        }
+    }
+
+    // Number of symbols needed to encode a digest, without padding.
+    const fn symbols(self) -> usize {
+        let bits = (DIGEST_SIZE_BYTES * 8) as u32;
+        bits.div_ceil(self.bits_per_symbol()) as usize
+    }
+
+    // Number of symbols of a digest including the padding.
+    const fn padded_length(self) -> usize {
+        match self {
+            Encoding::Hex => 32,
+            Encoding::Base64 | Encoding::Base64Url => 24,
+            Encoding::Base32 => 32,
+        }
+    }
+
+    const fn alphabet(self) -> &'static [u8] {
+        match self {
+            Encoding::Hex => HEX_ALPHABET,
+            Encoding::Base64 => BASE64_ALPHABET,
+            Encoding::Base64Url => BASE64_URL_ALPHABET,
+            Encoding::Base32 => BASE32_ALPHABET,
+        }
+    }
+
+    const fn symbol_value(self, symbol: u8) -> Option<u8> {
+        match (self, symbol) {
+            (Encoding::Hex, b'0'..=b'9') => Some(symbol - b'0'),
+            (Encoding::Hex, b'a'..=b'f') => Some(symbol - b'a' + 10),
+            (Encoding::Hex, b'A'..=b'F') => Some(symbol - b'A' + 10),
+            (Encoding::Base64 | Encoding::Base64Url, b'A'..=b'Z') => Some(symbol - b'A'),
+            (Encoding::Base64 | Encoding::Base64Url, b'a'..=b'z') => Some(symbol - b'a' + 26),
+            (Encoding::Base64 | Encoding::Base64Url, b'0'..=b'9') => Some(symbol - b'0' + 52),
+            (Encoding::Base64, b'+') | (Encoding::Base64Url, b'-') => Some(62),
+            (Encoding::Base64, b'/') | (Encoding::Base64Url, b'_') => Some(63),
+            (Encoding::Base32, b'A'..=b'Z') => Some(symbol - b'A'),
+            (Encoding::Base32, b'a'..=b'z') => Some(symbol - b'a'),
+            (Encoding::Base32, b'2'..=b'7') => Some(symbol - b'2' + 26),
+            _ => None,
+        }
+    }
+
+    /// Guess the encoding of a textual digest from its length and alphabet.
+    pub(crate) fn detect(text: &str) -> Result<Encoding, Md5Error> {
+        match text.len() {
+            32 if text.contains(PADDING as char) => Ok(Encoding::Base32),
+            32 => Ok(Encoding::Hex),
+            22 | 24 if text.contains(['-', '_']) => Ok(Encoding::Base64Url),
+            22 | 24 => Ok(Encoding::Base64),
+            26 => Ok(Encoding::Base32),
+            length => Err(Md5Error::InvalidDigestLength { length }),
+        }
+    }
+}
+
+fn invalid_character(text: &str, position: usize, encoding: Encoding) -> Md5Error {
+    // Every byte before `position` is a valid ASCII symbol, so it's always a char boundary.
+    let character = text[position..].chars().next().unwrap_or_default();
+    Md5Error::InvalidDigestCharacter {
+        position,
+        character,
+        encoding,
+    }
+}
+
+/// Decode a textual digest in [Encoding::Hex] in const context, returning `None` if it's not
+/// valid.
+pub const fn decode_hex(text: &str) -> Option<[u8; DIGEST_SIZE_BYTES]> {
+    let text = text.as_bytes();
+    if text.len() != Encoding::Hex.symbols() {
+        return None;
+    }
+    let mut result = [0_u8; DIGEST_SIZE_BYTES];
+    let mut position = 0;
+    while position < text.len() {
+        let value = match Encoding::Hex.symbol_value(text[position]) {
+            Some(value) => value,
+            None => return None,
+        };
+        result[position / 2] = (result[position / 2] << 4) | value;
+        position += 1;
+    }
+    Some(result)
+}
+
+/// Decode a textual digest using the given encoding.
+pub fn decode(text: &str, encoding: Encoding) -> Result<[u8; DIGEST_SIZE_BYTES], Md5Error> {
+    let length = text.len();
+    if length != encoding.symbols() && length != encoding.padded_length() {
+        return Err(Md5Error::InvalidDigestLength { length });
+    }
+    let (symbols, padding) = text.as_bytes().split_at(encoding.symbols());
+    let bits_per_symbol = encoding.bits_per_symbol();
+    let mut result = [0_u8; DIGEST_SIZE_BYTES];
+    let mut written = 0;
+    let mut accumulator = 0_u32;
+    let mut bits = 0;
+    for (position, symbol) in symbols.iter().enumerate() {
+        let value = encoding
+            .symbol_value(*symbol)
+            .ok_or_else(|| invalid_character(text, position, encoding))?;
+        accumulator = (accumulator << bits_per_symbol) | value as u32;
+        bits += bits_per_symbol;
+        if bits >= 8 {
+            bits -= 8;
+            result[written] = (accumulator >> bits) as u8;
+            written += 1;
+            accumulator &= (1 << bits) - 1;
+        }
+    }
+    if accumulator != 0 {
+        return Err(Md5Error::NonCanonicalDigest {
+            position: symbols.len() - 1,
+            encoding,
+        });
+    }
+    if let Some(offset) = padding.iter().position(|symbol| *symbol != PADDING) {
+        return Err(Md5Error::InvalidDigestPadding {
+            position: symbols.len() + offset,
+            encoding,
+        });
+    }
+    Ok(result)
+}
+
+/// Encode a digest using the given encoding.
+///
+/// Returns a buffer with the ASCII symbols, including the padding, and the number of them.
+pub fn encode(
+    value: &[u8; DIGEST_SIZE_BYTES],
+    encoding: Encoding,
+) -> ([u8; MAX_ENCODED_LENGTH], usize) {
+    let alphabet = encoding.alphabet();
+    let bits_per_symbol = encoding.bits_per_symbol();
+    let mask = (1 << bits_per_symbol) - 1;
+    let mut result = [PADDING; MAX_ENCODED_LENGTH];
+    let mut written = 0;
+    let mut accumulator = 0_u32;
+    let mut bits = 0;
+    for byte in value.iter() {
+        accumulator = (accumulator << 8) | *byte as u32;
+        bits += 8;
+        while bits >= bits_per_symbol {
+            bits -= bits_per_symbol;
+            result[written] = alphabet[((accumulator >> bits) & mask) as usize];
+            written += 1;
+        }
+        accumulator &= (1 << bits) - 1;
+    }
+    if bits > 0 {
+        result[written] = alphabet[((accumulator << (bits_per_symbol - bits)) & mask) as usize];
+    }
+    (result, encoding.padded_length())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    #[ctor::ctor]
+    fn init() {
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    const ABC_DIGEST: [u8; 16] = [
+        0x90, 0x01, 0x50, 0x98, 0x3c, 0xd2, 0x4f, 0xb0, 0xd6, 0x96, 0x3f, 0x7d, 0x28, 0xe1, 0x7f,
+        0x72,
+    ];
+
+    #[rstest]
+    #[case("900150983cd24fb0d6963f7d28e17f72", Encoding::Hex)]
+    #[case("900150983CD24FB0D6963F7D28E17F72", Encoding::Hex)]
+    #[case("kAFQmDzST7DWlj99KOF/cg==", Encoding::Base64)]
+    #[case("kAFQmDzST7DWlj99KOF/cg", Encoding::Base64)]
+    #[case("kAFQmDzST7DWlj99KOF_cg==", Encoding::Base64Url)]
+    #[case("kAFQmDzST7DWlj99KOF_cg", Encoding::Base64Url)]
+    #[case("SAAVBGB42JH3BVUWH56SRYL7OI======", Encoding::Base32)]
+    #[case("SAAVBGB42JH3BVUWH56SRYL7OI", Encoding::Base32)]
+    #[case("saavbgb42jh3bvuwh56sryl7oi", Encoding::Base32)]
+    fn test_detect_and_decode(#[case] text: &str, #[case] expected: Encoding) {
+        let encoding = Encoding::detect(text).unwrap();
+        assert_eq!(encoding, expected);
+        assert_eq!(decode(text, encoding).unwrap(), ABC_DIGEST);
+    }
+
+    #[rstest]
+    #[case("900150983cd24fb0d6963f7d28e17f7", Encoding::Hex)]
+    #[case("kAFQmDzST7DWlj99KOF/cg=", Encoding::Base64)]
+    #[case("SAAVBGB42JH3BVUWH56SRYL7OI===", Encoding::Base32)]
+    fn test_decode_invalid_length(#[case] text: &str, #[case] encoding: Encoding) {
+        let error = decode(text, encoding).unwrap_err();
+        assert!(matches!(error, Md5Error::InvalidDigestLength { length } if length == text.len()));
+    }
+
+    #[rstest]
+    #[case("900150983cd24fb0d6963f7d28e17g72", Encoding::Hex, 29, 'g')]
+    #[case("900150983cd24fb0d6963f7d28e17é7", Encoding::Hex, 29, 'é')]
+    #[case("kAFQmDzST7DWlj99KOF_cg==", Encoding::Base64, 19, '_')]
+    #[case("kAFQmDzST7DWlj99KOF/cg", Encoding::Base64Url, 19, '/')]
+    #[case("SAAVBGB42JH3BVUWH56SRYL8OI", Encoding::Base32, 23, '8')]
+    fn test_decode_invalid_character(
+        #[case] text: &str,
+        #[case] encoding: Encoding,
+        #[case] expected_position: usize,
+        #[case] expected_character: char,
+    ) {
+        let error = decode(text, encoding).unwrap_err();
+        assert!(matches!(
+            error,
+            Md5Error::InvalidDigestCharacter { position, character, encoding: error_encoding }
+                if position == expected_position
+                    && character == expected_character
+                    && error_encoding == encoding
+        ));
+    }
+
+    #[rstest]
+    #[case("kAFQmDzST7DWlj99KOF/cg=A", Encoding::Base64, 23)]
+    #[case("SAAVBGB42JH3BVUWH56SRYL7OI==A===", Encoding::Base32, 28)]
+    fn test_decode_invalid_padding(
+        #[case] text: &str,
+        #[case] encoding: Encoding,
+        #[case] expected_position: usize,
+    ) {
+        let error = decode(text, encoding).unwrap_err();
+        assert!(matches!(
+            error,
+            Md5Error::InvalidDigestPadding { position, .. } if position == expected_position
+        ));
+    }
+
+    #[rstest]
+    #[case("kAFQmDzST7DWlj99KOF/ch==", Encoding::Base64, 21)]
+    #[case("SAAVBGB42JH3BVUWH56SRYL7OJ", Encoding::Base32, 25)]
+    fn test_decode_non_canonical(
+        #[case] text: &str,
+        #[case] encoding: Encoding,
+        #[case] expected_position: usize,
+    ) {
+        let error = decode(text, encoding).unwrap_err();
+        assert!(matches!(
+            error,
+            Md5Error::NonCanonicalDigest { position, .. } if position == expected_position
+        ));
+    }
+
+    #[rstest]
+    #[case(Encoding::Hex, "900150983cd24fb0d6963f7d28e17f72")]
+    #[case(Encoding::Base64, "kAFQmDzST7DWlj99KOF/cg==")]
+    #[case(Encoding::Base64Url, "kAFQmDzST7DWlj99KOF_cg==")]
+    #[case(Encoding::Base32, "SAAVBGB42JH3BVUWH56SRYL7OI======")]
+    fn test_encode(#[case] encoding: Encoding, #[case] expected: &str) {
+        let (buffer, length) = encode(&ABC_DIGEST, encoding);
+        assert_eq!(&buffer[..length], expected.as_bytes());
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("900150983cd24fb0d6963f7d28e17f72a")]
+    fn test_detect_invalid_length(#[case] text: &str) {
+        let error = Encoding::detect(text).unwrap_err();
+        assert!(matches!(error, Md5Error::InvalidDigestLength { length } if length == text.len()));
+    }
+}
@@ -0,0 +1,562 @@
+//! Runtime-agnostic asynchronous hashing of [futures_io] readers and [futures_core] streams.
+//!
+//! Requires the `futures` feature.
+//!
+//! Besides the functions and adapters of this module, [Md5Hasher] implements [Sink] for any
+//! chunk of bytes, so a stream can be sent into it.
+
+use crate::hash::Hash;
+use crate::md5_error::Md5Error;
+use crate::{Md5Hasher, DEFAULT_READ_BUFFER_SIZE};
+
+use ::futures_core::Stream;
+use ::futures_io::{AsyncBufRead, AsyncRead};
+use ::futures_sink::Sink;
+use std::convert::Infallible;
+use std::future::poll_fn;
+use std::io::ErrorKind;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+fn read_result(result: std::io::Result<usize>, total: u64) -> Result<Option<usize>, Md5Error> {
+    match result {
+        Ok(readed) => Ok(Some(readed)),
+        Err(error) if error.kind() == ErrorKind::Interrupted => Ok(None),
+        Err(source) => Err(Md5Error::ReadError {
+            source,
+            bytes_read: total,
+        }),
+    }
+}
+
+/// Computes and returns the hash of the data that can be readed from the asynchronous `input`.
+///
+/// Reads interrupted by a signal are retried.
+///
+/// # Errors
+///
+/// Works like [Md5Hasher::hash].
+///
+/// # Examples
+///
+/// ```
+/// use ya_md5::futures::hash;
+///
+/// # futures::executor::block_on(async {
+/// let hash = hash(&mut "hello world".as_bytes())
+///     .await
+///     .expect("Unexpected error reading from a slice");
+/// let result = format!("{}", hash);
+/// assert_eq!(result, "5eb63bbbe01eeed093cb22bb8f5acdc3");
+/// # });
+/// ```
+pub async fn hash<R: AsyncRead + Unpin + ?Sized>(input: &mut R) -> Result<Hash, Md5Error> {
+    let mut hasher = Md5Hasher::default();
+    update_reader(&mut hasher, input).await?;
+    Ok(hasher.finalize())
+}
+
+/// Computes and returns the hash of the data that can be readed from the asynchronous `input`,
+/// hashing it directly from the buffer of the `input` without copying it.
+///
+/// # Errors
+///
+/// Works like [Md5Hasher::hash].
+pub async fn hash_buf_read<R: AsyncBufRead + Unpin + ?Sized>(
+    input: &mut R,
+) -> Result<Hash, Md5Error> {
+    let mut hasher = Md5Hasher::default();
+    update_buf_read(&mut hasher, input).await?;
+    Ok(hasher.finalize())
+}
+
+/// Process with the `hasher` all the data that can be readed from the asynchronous `input`,
+/// returning the number of bytes readed.
+///
+/// # Errors
+///
+/// Works like [Md5Hasher::update_reader].
+pub async fn update_reader<R: AsyncRead + Unpin + ?Sized>(
+    hasher: &mut Md5Hasher,
+    input: &mut R,
+) -> Result<u64, Md5Error> {
+    let mut buffer = vec![0; DEFAULT_READ_BUFFER_SIZE];
+    let mut total = 0;
+    loop {
+        let result = poll_fn(|cx| Pin::new(&mut *input).poll_read(cx, &mut buffer)).await;
+        let readed = match read_result(result, total)? {
+            Some(readed) => readed,
+            None => continue,
+        };
+        if readed == 0 {
+            break;
+        }
+        hasher.update(&buffer[..readed]);
+        total += readed as u64;
+    }
+    Ok(total)
+}
+
+/// Process with the `hasher` all the data that can be readed from the asynchronous `input`
+/// directly from its buffer, returning the number of bytes readed.
+///
+/// # Errors
+///
+/// Works like [Md5Hasher::update_reader].
+pub async fn update_buf_read<R: AsyncBufRead + Unpin + ?Sized>(
+    hasher: &mut Md5Hasher,
+    input: &mut R,
+) -> Result<u64, Md5Error> {
+    let mut total = 0;
+    loop {
+        let result = poll_fn(|cx| {
+            let buffer = ready!(Pin::new(&mut *input).poll_fill_buf(cx))?;
+            hasher.update(buffer);
+            Poll::Ready(Ok(buffer.len()))
+        })
+        .await;
+        let readed = match read_result(result, total)? {
+            Some(readed) => readed,
+            None => continue,
+        };
+        if readed == 0 {
+            break;
+        }
+        Pin::new(&mut *input).consume(readed);
+        total += readed as u64;
+    }
+    Ok(total)
+}
+
+/// Computes and returns the hash of the chunks of bytes yielded by the `stream`.
+///
+/// # Examples
+///
+/// ```
+/// use futures::stream;
+/// use ya_md5::futures::hash_stream;
+///
+/// # futures::executor::block_on(async {
+/// let hash = hash_stream(stream::iter(["hello", " ", "world"])).await;
+/// let result = format!("{}", hash);
+/// assert_eq!(result, "5eb63bbbe01eeed093cb22bb8f5acdc3");
+/// # });
+/// ```
+pub async fn hash_stream<S>(mut stream: S) -> Hash
+where
+    S: Stream + Unpin,
+    S::Item: AsRef<[u8]>,
+{
+    let mut hasher = Md5Hasher::default();
+    while let Some(chunk) = poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await {
+        hasher.update(chunk);
+    }
+    hasher.finalize()
+}
+
+/// Processes every chunk sent as if passed to [Md5Hasher::update], never failing.
+///
+/// # Examples
+///
+/// ```
+/// use bytes::Bytes;
+/// use futures::SinkExt;
+/// use ya_md5::Md5Hasher;
+///
+/// # futures::executor::block_on(async {
+/// let mut hasher = Md5Hasher::default();
+/// hasher.send(Bytes::from("hello ")).await.unwrap();
+/// hasher.send(Bytes::from("world")).await.unwrap();
+/// let result = format!("{}", hasher.finalize());
+/// assert_eq!(result, "5eb63bbbe01eeed093cb22bb8f5acdc3");
+/// # });
+/// ```
+impl<B: AsRef<[u8]>> Sink<B> for Md5Hasher {
+    type Error = Infallible;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: B) -> Result<(), Infallible> {
+        self.get_mut().update(item);
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// An [AsyncRead] adapter that computes the MD5 hash of every byte read through it.
+///
+/// The asynchronous version of [crate::HashingReader]. When the inner reader implements
+/// [AsyncBufRead] the adapter implements it too, hashing the bytes as they are consumed from a
+/// copy of at most [DEFAULT_READ_BUFFER_SIZE] bytes of the inner buffer.
+///
+/// # Examples
+///
+/// ```
+/// use futures::io::AsyncReadExt;
+/// use ya_md5::futures::HashingReader;
+///
+/// # futures::executor::block_on(async {
+/// let mut reader = HashingReader::new("hello world".as_bytes());
+/// let mut content = String::new();
+/// reader.read_to_string(&mut content).await.expect("Unexpected error reading from a slice");
+/// let (_, hash, length) = reader.into_parts();
+/// assert_eq!(content, "hello world");
+/// assert_eq!(format!("{}", hash), "5eb63bbbe01eeed093cb22bb8f5acdc3");
+/// assert_eq!(length, 11);
+/// # });
+/// ```
+pub struct HashingReader<R> {
+    inner: R,
+    hasher: Md5Hasher,
+    length: u64,
+    // Copy of the start of the inner buffer returned by the last `poll_fill_buf`, consumed up to
+    // `position`.
+    buffer: Vec<u8>,
+    position: usize,
+}
+
+impl<R: AsyncRead + Unpin> HashingReader<R> {
+    /// Creates an adapter that reads from `inner`.
+    pub fn new(inner: R) -> Self {
+        HashingReader {
+            inner,
+            hasher: Md5Hasher::default(),
+            length: 0,
+            buffer: Vec::new(),
+            position: 0,
+        }
+    }
+
+    /// Returns a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns the number of bytes read so far.
+    pub fn bytes_read(&self) -> u64 {
+        self.length
+    }
+
+    /// Consumes the adapter, returning the inner reader, the hash of the bytes read and the
+    /// number of them.
+    pub fn into_parts(self) -> (R, Hash, u64) {
+        (self.inner, self.hasher.finalize(), self.length)
+    }
+
+    fn process(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+        self.length += data.len() as u64;
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for HashingReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        // The bytes not consumed yet are read again from the inner reader.
+        this.buffer.clear();
+        this.position = 0;
+        let readed = ready!(Pin::new(&mut this.inner).poll_read(cx, buf))?;
+        this.process(&buf[..readed]);
+        Poll::Ready(Ok(readed))
+    }
+}
+
+impl<R: AsyncBufRead + Unpin> AsyncBufRead for HashingReader<R> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+        let this = self.get_mut();
+        if this.position == this.buffer.len() {
+            let available = ready!(Pin::new(&mut this.inner).poll_fill_buf(cx))?;
+            let length = available.len().min(DEFAULT_READ_BUFFER_SIZE);
+            this.buffer.clear();
+            this.buffer.extend_from_slice(&available[..length]);
+            this.position = 0;
+        }
+        Poll::Ready(Ok(&this.buffer[this.position..]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.get_mut();
+        let end = this.position + amt.min(this.buffer.len() - this.position);
+        this.hasher.update(&this.buffer[this.position..end]);
+        this.length += (end - this.position) as u64;
+        Pin::new(&mut this.inner).consume(end - this.position);
+        this.position = end;
+    }
+}
+
+/// A [Stream] adapter that computes the MD5 hash of every chunk of bytes yielded through it.
+///
+/// # Examples
+///
+/// ```
+/// use futures::{stream, StreamExt};
+/// use ya_md5::futures::HashingStream;
+///
+/// # futures::executor::block_on(async {
+/// let mut chunks = HashingStream::new(stream::iter(["hello", " ", "world"]));
+/// while let Some(chunk) = chunks.next().await {
+///     // Process the chunk.
+/// }
+/// let (_, hash, length) = chunks.into_parts();
+/// assert_eq!(format!("{}", hash), "5eb63bbbe01eeed093cb22bb8f5acdc3");
+/// assert_eq!(length, 11);
+/// # });
+/// ```
+pub struct HashingStream<S> {
+    inner: S,
+    hasher: Md5Hasher,
+    length: u64,
+}
+
+impl<S> HashingStream<S>
+where
+    S: Stream + Unpin,
+    S::Item: AsRef<[u8]>,
+{
+    /// Creates an adapter that yields the chunks of `inner`.
+    pub fn new(inner: S) -> Self {
+        HashingStream {
+            inner,
+            hasher: Md5Hasher::default(),
+            length: 0,
+        }
+    }
+
+    /// Returns a reference to the inner stream.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Returns the number of bytes yielded so far.
+    pub fn bytes_read(&self) -> u64 {
+        self.length
+    }
+
+    /// Consumes the adapter, returning the inner stream, the hash of the bytes yielded and the
+    /// number of them.
+    pub fn into_parts(self) -> (S, Hash, u64) {
+        (self.inner, self.hasher.finalize(), self.length)
+    }
+}
+
+impl<S> Stream for HashingStream<S>
+where
+    S: Stream + Unpin,
+    S::Item: AsRef<[u8]>,
+{
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<S::Item>> {
+        let this = self.get_mut();
+        let item = ready!(Pin::new(&mut this.inner).poll_next(cx));
+        if let Some(chunk) = &item {
+            let data = chunk.as_ref();
+            this.hasher.update(data);
+            this.length += data.len() as u64;
+        }
+        Poll::Ready(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::futures::executor::block_on;
+    use ::futures::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+    use ::futures::{stream, SinkExt, StreamExt};
+    use bytes::Bytes;
+    use rstest::rstest;
+
+    #[ctor::ctor]
+    fn init() {
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    const DATA: &str =
+        "12345678901234567890123456789012345678901234567890123456789012345678901234567890";
+    const EXPECTED: &str = "57edf4a22be3c955ac49da2e2107b67a";
+
+    // A reader that fails after yielding its data, once interrupted.
+    struct FailingReader {
+        data: &'static [u8],
+        interrupted: bool,
+    }
+
+    impl AsyncRead for FailingReader {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<std::io::Result<usize>> {
+            let this = self.get_mut();
+            if !this.interrupted {
+                this.interrupted = true;
+                return Poll::Ready(Err(ErrorKind::Interrupted.into()));
+            }
+            if this.data.is_empty() {
+                return Poll::Ready(Err(ErrorKind::Other.into()));
+            }
+            let readed = buf.len().min(this.data.len());
+            buf[..readed].copy_from_slice(&this.data[..readed]);
+            this.data = &this.data[readed..];
+            Poll::Ready(Ok(readed))
+        }
+    }
+
+    #[rstest]
+    fn test_hash() {
+        let hash = block_on(hash(&mut DATA.as_bytes())).unwrap();
+        assert_eq!(hash, EXPECTED);
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(64)]
+    #[case(100)]
+    fn test_hash_buf_read(#[case] capacity: usize) {
+        let mut reader = BufReader::with_capacity(capacity, DATA.as_bytes());
+        let hash = block_on(hash_buf_read(&mut reader)).unwrap();
+        assert_eq!(hash, EXPECTED);
+    }
+
+    #[rstest]
+    fn test_hash_error() {
+        let mut reader = FailingReader {
+            data: DATA.as_bytes(),
+            interrupted: false,
+        };
+        let error = block_on(hash(&mut reader)).unwrap_err();
+        assert!(matches!(error, Md5Error::ReadError { bytes_read: 80, .. }));
+        let mut reader = BufReader::with_capacity(
+            7,
+            FailingReader {
+                data: DATA.as_bytes(),
+                interrupted: false,
+            },
+        );
+        let error = block_on(hash_buf_read(&mut reader)).unwrap_err();
+        assert!(matches!(error, Md5Error::ReadError { bytes_read: 80, .. }));
+    }
+
+    #[rstest]
+    fn test_hashing_reader() {
+        let mut reader = HashingReader::new(DATA.as_bytes());
+        let mut content = Vec::new();
+        block_on(reader.read_to_end(&mut content)).unwrap();
+        let (_, hash, length) = reader.into_parts();
+        assert_eq!(content, DATA.as_bytes());
+        assert_eq!(hash, EXPECTED);
+        assert_eq!(length, DATA.len() as u64);
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(7)]
+    #[case(64)]
+    fn test_hashing_reader_buf_read(#[case] capacity: usize) {
+        let mut reader = HashingReader::new(BufReader::with_capacity(capacity, DATA.as_bytes()));
+        let mut line = String::new();
+        block_on(reader.read_line(&mut line)).unwrap();
+        let (_, hash, length) = reader.into_parts();
+        assert_eq!(line, DATA);
+        assert_eq!(hash, EXPECTED);
+        assert_eq!(length, DATA.len() as u64);
+    }
+
+    // A buffered reader that is pending every other call to `poll_fill_buf`.
+    struct PendingReader {
+        data: &'static [u8],
+        pending: bool,
+    }
+
+    impl AsyncRead for PendingReader {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<std::io::Result<usize>> {
+            Pin::new(&mut self.get_mut().data).poll_read(cx, buf)
+        }
+    }
+
+    impl AsyncBufRead for PendingReader {
+        fn poll_fill_buf(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<std::io::Result<&[u8]>> {
+            let this = self.get_mut();
+            this.pending = !this.pending;
+            if this.pending {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            Poll::Ready(Ok(this.data))
+        }
+
+        fn consume(self: Pin<&mut Self>, amt: usize) {
+            let this = self.get_mut();
+            this.data = &this.data[amt..];
+        }
+    }
+
+    #[rstest]
+    fn test_hashing_reader_pending_buf_read() {
+        let mut reader = HashingReader::new(PendingReader {
+            data: DATA.as_bytes(),
+            pending: false,
+        });
+        let mut line = String::new();
+        block_on(reader.read_line(&mut line)).unwrap();
+        let (_, hash, length) = reader.into_parts();
+        assert_eq!(line, DATA);
+        assert_eq!(hash, EXPECTED);
+        assert_eq!(length, DATA.len() as u64);
+    }
+
+    #[rstest]
+    fn test_hash_stream() {
+        let chunks = DATA.as_bytes().chunks(7).map(Bytes::copy_from_slice);
+        let hash = block_on(hash_stream(stream::iter(chunks)));
+        assert_eq!(hash, EXPECTED);
+    }
+
+    #[rstest]
+    fn test_hashing_stream() {
+        let chunks: Vec<_> = DATA.as_bytes().chunks(7).collect();
+        let mut hashing = HashingStream::new(stream::iter(chunks.clone()));
+        let yielded: Vec<_> = block_on((&mut hashing).collect());
+        let (_, hash, length) = hashing.into_parts();
+        assert_eq!(yielded, chunks);
+        assert_eq!(hash, EXPECTED);
+        assert_eq!(length, DATA.len() as u64);
+    }
+
+    #[rstest]
+    fn test_sink() {
+        let mut hasher = Md5Hasher::default();
+        let chunks = DATA
+            .as_bytes()
+            .chunks(7)
+            .map(|chunk| Ok(Bytes::copy_from_slice(chunk)));
+        block_on(hasher.send_all(&mut stream::iter(chunks))).unwrap();
+        block_on(SinkExt::<Bytes>::close(&mut hasher)).unwrap();
+        assert_eq!(hasher.finalize(), EXPECTED);
+    }
+}
@@ -1,22 +1,203 @@
-use std::fmt::Display;
+use crate::encoding::{decode, decode_hex, Encoding};
+use crate::md5_error::Md5Error;
 
-/// The hash computed by the [Md5Hasher](crate::Md5Hasher).
-#[derive(Debug)]
+use core::hint::black_box;
+use core::str::FromStr;
+
+// Compares without short-circuiting so the time taken doesn't depend on the position of the first
+// different byte; only the lengths, which are public, can return early.
+pub(crate) fn ct_eq_bytes(left: &[u8], right: &[u8]) -> bool {
+    if left.len() != right.len() {
+        return false;
+    }
+    let difference = left
+        .iter()
+        .zip(right.iter())
+        .fold(0_u8, |accumulator, (left, right)| {
+            accumulator | (left ^ right)
+        });
+    black_box(difference) == 0
+}
+
+/// A 128-bit digest, as computed by the [Md5Hasher](crate::Md5Hasher) and the
+/// [Md4Hasher](crate::Md4Hasher).
+///
+/// Besides being computed, a hash can be parsed from its text representation in any of the
+/// supported [Encoding]s, and formatted with them through [Hash::encode].
+///
+/// # Examples
+///
+/// ```
+/// use ya_md5::Hash;
+///
+/// let hash: Hash = "kAFQmDzST7DWlj99KOF/cg==".parse().expect("Invalid digest");
+/// assert_eq!(format!("{}", hash), "900150983cd24fb0d6963f7d28e17f72");
+/// ```
+///
+/// Every comparison between hashes, or between a hash and its raw bytes or hexadecimal text, is
+/// done in constant time.
+#[derive(Debug, Copy, Clone)]
 pub struct Hash {
     value: [u8; 16],
 }
 
+impl Hash {
+    /// Parses the text of a digest using the given `encoding`.
+    ///
+    /// # Errors
+    ///
+    /// If the text is not a valid digest in the `encoding` an error is returned describing the
+    /// position and the reason.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ya_md5::{Encoding, Hash};
+    ///
+    /// let hash = Hash::decode("SAAVBGB42JH3BVUWH56SRYL7OI", Encoding::Base32)
+    ///     .expect("Invalid digest");
+    /// assert_eq!(format!("{}", hash), "900150983cd24fb0d6963f7d28e17f72");
+    /// ```
+    pub fn decode(text: &str, encoding: Encoding) -> Result<Hash, Md5Error> {
+        decode(text, encoding).map(Hash::from)
+    }
+
+    /// Compares two hashes in constant time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ya_md5::{Hash, Md5Hasher};
+    ///
+    /// let expected: Hash = "5eb63bbbe01eeed093cb22bb8f5acdc3".parse().expect("Invalid digest");
+    /// assert!(Md5Hasher::hash_str("hello world").ct_eq(&expected));
+    /// ```
+    pub fn ct_eq(&self, other: &Hash) -> bool {
+        ct_eq_bytes(&self.value, &other.value)
+    }
+
+    /// Parses the text of a digest in [Encoding::Hex] in const context, returning `None` if
+    /// it's not valid; see the [hash!](crate::hash!) macro to check it at compile time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ya_md5::Hash;
+    ///
+    /// const EXPECTED: Option<Hash> = Hash::from_hex("900150983cd24fb0d6963f7d28e17f72");
+    /// assert!(EXPECTED.is_some());
+    /// assert!(Hash::from_hex("not a digest").is_none());
+    /// ```
+    pub const fn from_hex(text: &str) -> Option<Hash> {
+        match decode_hex(text) {
+            Some(value) => Some(Hash::from_bytes(value)),
+            None => None,
+        }
+    }
+
+    /// Creates a hash from the bytes of the digest, like its [From] implementation but usable in
+    /// const context.
+    pub const fn from_bytes(value: [u8; 16]) -> Hash {
+        Hash { value }
+    }
+
+    /// Returns the bytes of the digest.
+    pub const fn as_bytes(&self) -> &[u8; 16] {
+        &self.value
+    }
+}
+
 impl From<[u8; 16]> for Hash {
     fn from(value: [u8; 16]) -> Hash {
-        Hash { value }
+        Hash::from_bytes(value)
+    }
+}
+
+impl PartialEq for Hash {
+    fn eq(&self, other: &Hash) -> bool {
+        self.ct_eq(other)
+    }
+}
+
+impl Eq for Hash {}
+
+impl PartialEq<[u8; 16]> for Hash {
+    fn eq(&self, other: &[u8; 16]) -> bool {
+        ct_eq_bytes(&self.value, other)
     }
 }
 
-impl Display for Hash {
-    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for value in self.value.iter() {
-            write!(formatter, "{:02x}", value)?
+/// Compares the hash with a text in [Encoding::Hex], either lowercase or uppercase; a text that
+/// is not a valid hexadecimal digest is never equal.
+impl PartialEq<&str> for Hash {
+    fn eq(&self, other: &&str) -> bool {
+        match decode(other, Encoding::Hex) {
+            Ok(value) => ct_eq_bytes(&self.value, &value),
+            Err(_) => false,
         }
-        Ok(())
+    }
+}
+
+impl FromStr for Hash {
+    type Err = Md5Error;
+
+    /// Parses the text of a digest, guessing its [Encoding] from the length and the alphabet.
+    fn from_str(text: &str) -> Result<Hash, Md5Error> {
+        Hash::decode(text, Encoding::detect(text)?)
+    }
+}
+
+impl TryFrom<&str> for Hash {
+    type Error = Md5Error;
+
+    fn try_from(text: &str) -> Result<Hash, Md5Error> {
+        Hash::from_str(text)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Hash;
+    use rstest::rstest;
+
+    #[ctor::ctor]
+    fn init() {
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    const ABC_DIGEST: [u8; 16] = [
+        0x90, 0x01, 0x50, 0x98, 0x3c, 0xd2, 0x4f, 0xb0, 0xd6, 0x96, 0x3f, 0x7d, 0x28, 0xe1, 0x7f,
+        0x72,
+    ];
+
+    #[rstest]
+    #[case(ABC_DIGEST, true)]
+    #[case([0; 16], false)]
+    #[case({ let mut value = ABC_DIGEST; value[15] ^= 1; value }, false)]
+    fn test_eq(#[case] other: [u8; 16], #[case] expected: bool) {
+        let hash = Hash::from(ABC_DIGEST);
+        assert_eq!(hash.ct_eq(&Hash::from(other)), expected);
+        assert_eq!(hash == Hash::from(other), expected);
+        assert_eq!(hash == other, expected);
+    }
+
+    #[rstest]
+    #[case("900150983cd24fb0d6963f7d28e17f72", true)]
+    #[case("900150983CD24FB0D6963F7D28E17F72", true)]
+    #[case("900150983cd24fb0d6963f7d28e17f73", false)]
+    #[case("kAFQmDzST7DWlj99KOF/cg==", false)]
+    #[case("not a digest", false)]
+    fn test_eq_str(#[case] other: &str, #[case] expected: bool) {
+        assert_eq!(Hash::from(ABC_DIGEST) == other, expected);
+    }
+
+    #[rstest]
+    #[case("900150983cd24fb0d6963f7d28e17f72", Some(ABC_DIGEST))]
+    #[case("900150983CD24FB0D6963F7D28E17F72", Some(ABC_DIGEST))]
+    #[case("900150983cd24fb0d6963f7d28e17f7", None)]
+    #[case("900150983cd24fb0d6963f7d28e17f7g", None)]
+    #[case("kAFQmDzST7DWlj99KOF/cg==", None)]
+    fn test_from_hex(#[case] text: &str, #[case] expected: Option<[u8; 16]>) {
+        assert_eq!(Hash::from_hex(text), expected.map(Hash::from));
     }
 }
@@ -1,14 +1,15 @@
 use crate::chunk::Chunk;
 use crate::chunk::CHUNK_SIZE_BYTES;
+use crate::compression_function::{CompressionFunction, Endianness};
 use crate::conversions::u32_to_u8;
 use crate::conversions::u8_to_u32;
+use crate::hash::Hash;
 
-use log::trace;
-use std::fmt::Display;
+use core::fmt::Display;
 
-const BLOCK_SIZE_WORDS: usize = CHUNK_SIZE_BYTES / 4;
+pub const BLOCK_SIZE_WORDS: usize = CHUNK_SIZE_BYTES / 4;
 // Precomputed table for T[i] = floor(2^32 * abs(sin(i))) for i = 1..64
-const SINE_TABLE: [u32; 64] = [
+pub const SINE_TABLE: [u32; 64] = [
     0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613, 0xfd469501,
     0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821,
     0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
@@ -18,29 +19,33 @@ const SINE_TABLE: [u32; 64] = [
     0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
     0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, 0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391,
 ];
-const INITIAL_WORD_A: u32 = 0x67452301;
-const INITIAL_WORD_B: u32 = 0xefcdab89;
-const INITIAL_WORD_C: u32 = 0x98badcfe;
-const INITIAL_WORD_D: u32 = 0x10325476;
+pub const INITIAL_WORD_A: u32 = 0x67452301;
+pub const INITIAL_WORD_B: u32 = 0xefcdab89;
+pub const INITIAL_WORD_C: u32 = 0x98badcfe;
+pub const INITIAL_WORD_D: u32 = 0x10325476;
 
 type Block = [u32; BLOCK_SIZE_WORDS];
 
-const fn aux_fun_f(x: u32, y: u32, z: u32) -> u32 {
+pub const fn aux_fun_f(x: u32, y: u32, z: u32) -> u32 {
     (x & y) | (!(x) & z)
 }
 
-const fn aux_fun_g(x: u32, y: u32, z: u32) -> u32 {
+pub const fn aux_fun_g(x: u32, y: u32, z: u32) -> u32 {
     (x & z) | (y & !(z))
 }
 
-const fn aux_fun_h(x: u32, y: u32, z: u32) -> u32 {
+pub const fn aux_fun_h(x: u32, y: u32, z: u32) -> u32 {
     x ^ y ^ z
 }
 
-const fn aux_fun_i(x: u32, y: u32, z: u32) -> u32 {
+pub const fn aux_fun_i(x: u32, y: u32, z: u32) -> u32 {
     y ^ (x | !(z))
 }
 
+/// The four words of the MD5 state, updated by its [CompressionFunction].
+///
+/// The default state holds the initial values of RFC 1321; any other one can be used with a
+/// [ChunkProcessor](crate::ChunkProcessor) to compute MD5 with custom initial values.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct HashComputeState {
     a: u32,
@@ -50,7 +55,7 @@ pub struct HashComputeState {
 }
 
 impl Display for HashComputeState {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(
             f,
             "HashComputeState {{ a: {:0>8x}, b: {:0>8x}, c: {:0>8x}, d: {:0>8x} }}",
@@ -60,6 +65,18 @@ impl Display for HashComputeState {
     }
 }
 
+macro_rules! Md5Step {
+    ($aux_fun:ident, $a:ident, $b:ident, $c:ident, $d:ident, $x:expr, $s:expr, $t:expr) => {
+        $a = $a
+            .wrapping_add($aux_fun($b, $c, $d))
+            .wrapping_add($x)
+            .wrapping_add($t)
+            .rotate_left($s)
+            .wrapping_add($b)
+    };
+}
+
+#[cfg(test)]
 macro_rules! Md5Op {
     ($self:ident, $block:ident, $aux_fun:ident, $a:ident, $b:ident, $c:ident, $d:ident, $k:expr, $s:expr, $i:expr) => {
         HashComputeState {
@@ -81,6 +98,13 @@ macro_rules! Md5Op {
 
 impl Default for HashComputeState {
     fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HashComputeState {
+    /// Creates the initial state of MD5.
+    pub const fn new() -> Self {
         HashComputeState {
             a: INITIAL_WORD_A,
             b: INITIAL_WORD_B,
@@ -88,10 +112,39 @@ impl Default for HashComputeState {
             d: INITIAL_WORD_D,
         }
     }
-}
 
-impl HashComputeState {
-    pub fn advance_step(self, block: &Block, step: u8) -> Self {
+    /// Creates a state from its words a, b, c and d.
+    pub const fn from_words(words: [u32; 4]) -> Self {
+        HashComputeState {
+            a: words[0],
+            b: words[1],
+            c: words[2],
+            d: words[3],
+        }
+    }
+
+    /// Creates a state from a digest, the inverse of [HashComputeState::to_raw].
+    pub fn from_raw(raw: &[u8; 16]) -> Self {
+        let mut words = [0; 4];
+        for (index, word) in words.iter_mut().enumerate() {
+            let unpacked: [u8; 4] = [
+                raw[index * 4],
+                raw[index * 4 + 1],
+                raw[index * 4 + 2],
+                raw[index * 4 + 3],
+            ];
+            *word = u8_to_u32(&unpacked);
+        }
+        Self::from_words(words)
+    }
+
+    /// Returns the words a, b, c and d of the state.
+    pub const fn to_words(self) -> [u32; 4] {
+        [self.a, self.b, self.c, self.d]
+    }
+
+    #[cfg(test)]
+    pub(crate) fn advance_step(self, block: &Block, step: u8) -> Self {
         match step {
             // Round 1
             1 => Md5Op!(self, block, aux_fun_f, a, b, c, d, 0, 7, 0), // [ABCD  0  7  1]
@@ -165,23 +218,115 @@ impl HashComputeState {
         }
     }
 
-    pub fn process_chunk(self, chunk: &Chunk) -> Self {
-        log::debug!("Chunk: {:?}", chunk);
+    /// Returns the state after compressing the `chunk`, also in const context.
+    // Fully unrolled compression of a chunk, with the words of the state kept in locals.
+    #[inline]
+    pub const fn process_chunk(self, chunk: &Chunk) -> Self {
+        let mut block: Block = [0; BLOCK_SIZE_WORDS];
+        let mut index = 0;
+        while index < BLOCK_SIZE_WORDS {
+            let offset = index * 4;
+            block[index] = u32::from_le_bytes([
+                chunk[offset],
+                chunk[offset + 1],
+                chunk[offset + 2],
+                chunk[offset + 3],
+            ]);
+            index += 1;
+        }
+        let HashComputeState {
+            mut a,
+            mut b,
+            mut c,
+            mut d,
+        } = self;
+        // Round 1
+        Md5Step!(aux_fun_f, a, b, c, d, block[0], 7, SINE_TABLE[0]);
+        Md5Step!(aux_fun_f, d, a, b, c, block[1], 12, SINE_TABLE[1]);
+        Md5Step!(aux_fun_f, c, d, a, b, block[2], 17, SINE_TABLE[2]);
+        Md5Step!(aux_fun_f, b, c, d, a, block[3], 22, SINE_TABLE[3]);
+        Md5Step!(aux_fun_f, a, b, c, d, block[4], 7, SINE_TABLE[4]);
+        Md5Step!(aux_fun_f, d, a, b, c, block[5], 12, SINE_TABLE[5]);
+        Md5Step!(aux_fun_f, c, d, a, b, block[6], 17, SINE_TABLE[6]);
+        Md5Step!(aux_fun_f, b, c, d, a, block[7], 22, SINE_TABLE[7]);
+        Md5Step!(aux_fun_f, a, b, c, d, block[8], 7, SINE_TABLE[8]);
+        Md5Step!(aux_fun_f, d, a, b, c, block[9], 12, SINE_TABLE[9]);
+        Md5Step!(aux_fun_f, c, d, a, b, block[10], 17, SINE_TABLE[10]);
+        Md5Step!(aux_fun_f, b, c, d, a, block[11], 22, SINE_TABLE[11]);
+        Md5Step!(aux_fun_f, a, b, c, d, block[12], 7, SINE_TABLE[12]);
+        Md5Step!(aux_fun_f, d, a, b, c, block[13], 12, SINE_TABLE[13]);
+        Md5Step!(aux_fun_f, c, d, a, b, block[14], 17, SINE_TABLE[14]);
+        Md5Step!(aux_fun_f, b, c, d, a, block[15], 22, SINE_TABLE[15]);
+        // Round 2
+        Md5Step!(aux_fun_g, a, b, c, d, block[1], 5, SINE_TABLE[16]);
+        Md5Step!(aux_fun_g, d, a, b, c, block[6], 9, SINE_TABLE[17]);
+        Md5Step!(aux_fun_g, c, d, a, b, block[11], 14, SINE_TABLE[18]);
+        Md5Step!(aux_fun_g, b, c, d, a, block[0], 20, SINE_TABLE[19]);
+        Md5Step!(aux_fun_g, a, b, c, d, block[5], 5, SINE_TABLE[20]);
+        Md5Step!(aux_fun_g, d, a, b, c, block[10], 9, SINE_TABLE[21]);
+        Md5Step!(aux_fun_g, c, d, a, b, block[15], 14, SINE_TABLE[22]);
+        Md5Step!(aux_fun_g, b, c, d, a, block[4], 20, SINE_TABLE[23]);
+        Md5Step!(aux_fun_g, a, b, c, d, block[9], 5, SINE_TABLE[24]);
+        Md5Step!(aux_fun_g, d, a, b, c, block[14], 9, SINE_TABLE[25]);
+        Md5Step!(aux_fun_g, c, d, a, b, block[3], 14, SINE_TABLE[26]);
+        Md5Step!(aux_fun_g, b, c, d, a, block[8], 20, SINE_TABLE[27]);
+        Md5Step!(aux_fun_g, a, b, c, d, block[13], 5, SINE_TABLE[28]);
+        Md5Step!(aux_fun_g, d, a, b, c, block[2], 9, SINE_TABLE[29]);
+        Md5Step!(aux_fun_g, c, d, a, b, block[7], 14, SINE_TABLE[30]);
+        Md5Step!(aux_fun_g, b, c, d, a, block[12], 20, SINE_TABLE[31]);
+        // Round 3
+        Md5Step!(aux_fun_h, a, b, c, d, block[5], 4, SINE_TABLE[32]);
+        Md5Step!(aux_fun_h, d, a, b, c, block[8], 11, SINE_TABLE[33]);
+        Md5Step!(aux_fun_h, c, d, a, b, block[11], 16, SINE_TABLE[34]);
+        Md5Step!(aux_fun_h, b, c, d, a, block[14], 23, SINE_TABLE[35]);
+        Md5Step!(aux_fun_h, a, b, c, d, block[1], 4, SINE_TABLE[36]);
+        Md5Step!(aux_fun_h, d, a, b, c, block[4], 11, SINE_TABLE[37]);
+        Md5Step!(aux_fun_h, c, d, a, b, block[7], 16, SINE_TABLE[38]);
+        Md5Step!(aux_fun_h, b, c, d, a, block[10], 23, SINE_TABLE[39]);
+        Md5Step!(aux_fun_h, a, b, c, d, block[13], 4, SINE_TABLE[40]);
+        Md5Step!(aux_fun_h, d, a, b, c, block[0], 11, SINE_TABLE[41]);
+        Md5Step!(aux_fun_h, c, d, a, b, block[3], 16, SINE_TABLE[42]);
+        Md5Step!(aux_fun_h, b, c, d, a, block[6], 23, SINE_TABLE[43]);
+        Md5Step!(aux_fun_h, a, b, c, d, block[9], 4, SINE_TABLE[44]);
+        Md5Step!(aux_fun_h, d, a, b, c, block[12], 11, SINE_TABLE[45]);
+        Md5Step!(aux_fun_h, c, d, a, b, block[15], 16, SINE_TABLE[46]);
+        Md5Step!(aux_fun_h, b, c, d, a, block[2], 23, SINE_TABLE[47]);
+        // Round 4
+        Md5Step!(aux_fun_i, a, b, c, d, block[0], 6, SINE_TABLE[48]);
+        Md5Step!(aux_fun_i, d, a, b, c, block[7], 10, SINE_TABLE[49]);
+        Md5Step!(aux_fun_i, c, d, a, b, block[14], 15, SINE_TABLE[50]);
+        Md5Step!(aux_fun_i, b, c, d, a, block[5], 21, SINE_TABLE[51]);
+        Md5Step!(aux_fun_i, a, b, c, d, block[12], 6, SINE_TABLE[52]);
+        Md5Step!(aux_fun_i, d, a, b, c, block[3], 10, SINE_TABLE[53]);
+        Md5Step!(aux_fun_i, c, d, a, b, block[10], 15, SINE_TABLE[54]);
+        Md5Step!(aux_fun_i, b, c, d, a, block[1], 21, SINE_TABLE[55]);
+        Md5Step!(aux_fun_i, a, b, c, d, block[8], 6, SINE_TABLE[56]);
+        Md5Step!(aux_fun_i, d, a, b, c, block[15], 10, SINE_TABLE[57]);
+        Md5Step!(aux_fun_i, c, d, a, b, block[6], 15, SINE_TABLE[58]);
+        Md5Step!(aux_fun_i, b, c, d, a, block[13], 21, SINE_TABLE[59]);
+        Md5Step!(aux_fun_i, a, b, c, d, block[4], 6, SINE_TABLE[60]);
+        Md5Step!(aux_fun_i, d, a, b, c, block[11], 10, SINE_TABLE[61]);
+        Md5Step!(aux_fun_i, c, d, a, b, block[2], 15, SINE_TABLE[62]);
+        Md5Step!(aux_fun_i, b, c, d, a, block[9], 21, SINE_TABLE[63]);
+        HashComputeState {
+            a: self.a.wrapping_add(a),
+            b: self.b.wrapping_add(b),
+            c: self.c.wrapping_add(c),
+            d: self.d.wrapping_add(d),
+        }
+    }
+
+    // Step by step compression of a chunk, kept as the reference for the unrolled one.
+    #[cfg(test)]
+    pub(crate) fn process_chunk_reference(self, chunk: &Chunk) -> Self {
         let mut block: Block = [0; BLOCK_SIZE_WORDS];
-        for (index, item) in block.iter_mut().enumerate() {
-            let unpacked: [u8; 4] = match chunk[(index * 4)..((index * 4) + 4)].try_into() {
-                Ok(value) => value,
-                Err(_) => panic!(
-                    "process_chunk: error extracting word; position={:?}, chunk={:?}",
-                    index, chunk
-                ),
-            };
-            *item = u8_to_u32(&unpacked);
+        for (item, bytes) in block.iter_mut().zip(chunk.chunks_exact(4)) {
+            *item = u8_to_u32(&[bytes[0], bytes[1], bytes[2], bytes[3]]);
         }
         let mut result = self;
         for step in 1..65 {
             result = result.advance_step(&block, step);
-            trace!("State at step {:0>2}: {}", step, result);
+            log::trace!("State at step {:0>2}: {}", step, result);
         }
         HashComputeState {
             a: self.a.wrapping_add(result.a),
@@ -191,16 +336,35 @@ impl HashComputeState {
         }
     }
 
-    pub fn to_raw(self) -> [u8; 16] {
+    /// Returns the digest from the state, its words in little endian.
+    pub const fn to_raw(self) -> [u8; 16] {
+        let words = self.to_words();
         let mut buffer: [u8; 16] = [0; 16];
-        buffer[0..4].copy_from_slice(&u32_to_u8(&self.a));
-        buffer[4..8].copy_from_slice(&u32_to_u8(&self.b));
-        buffer[8..12].copy_from_slice(&u32_to_u8(&self.c));
-        buffer[12..16].copy_from_slice(&u32_to_u8(&self.d));
+        let mut index = 0;
+        while index < buffer.len() {
+            buffer[index] = u32_to_u8(&words[index / 4])[index % 4];
+            index += 1;
+        }
         buffer
     }
 }
 
+impl CompressionFunction for HashComputeState {
+    type Block = Chunk;
+    type Output = Hash;
+    const BLOCK_SIZE: usize = CHUNK_SIZE_BYTES;
+    const EMPTY_BLOCK: Chunk = [0; CHUNK_SIZE_BYTES];
+    const LENGTH_ENDIANNESS: Endianness = Endianness::Little;
+
+    fn compress(self, block: &Chunk) -> Self {
+        self.process_chunk(block)
+    }
+
+    fn output(self) -> Hash {
+        Hash::from(self.to_raw())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -268,4 +432,65 @@ mod test {
         instance = instance.process_chunk(&chunk);
         assert_eq!(instance, expected);
     }
+
+    #[rstest]
+    fn test_from_raw() {
+        let instance = HashComputeState {
+            a: 0xd98c1dd4,
+            b: 0x04b2008f,
+            c: 0x980980e9,
+            d: 0x7e42f8ec,
+        };
+        assert_eq!(HashComputeState::from_raw(&instance.to_raw()), instance);
+    }
+
+    fn chunk_with_seed(seed: u8) -> Chunk {
+        let mut chunk: Chunk = [0; CHUNK_SIZE_BYTES];
+        for (index, byte) in chunk.iter_mut().enumerate() {
+            *byte = (index as u8).wrapping_mul(31).wrapping_add(seed);
+        }
+        chunk
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(1)]
+    #[case(0x80)]
+    #[case(0xff)]
+    fn test_process_chunk_matches_reference(#[case] seed: u8) {
+        let chunk = chunk_with_seed(seed);
+        let mut instance = HashComputeState::default();
+        let mut reference = HashComputeState::default();
+        for _ in 0..4 {
+            instance = instance.process_chunk(&chunk);
+            reference = reference.process_chunk_reference(&chunk);
+            assert_eq!(instance, reference);
+        }
+    }
+
+    // Compares the throughput of both compression functions, run it with
+    // `cargo test --release -- --ignored --nocapture`.
+    #[rstest]
+    #[ignore]
+    fn test_process_chunk_throughput() {
+        const CHUNKS: u32 = 1 << 20;
+        let chunk = chunk_with_seed(0);
+        let measure = |compress: fn(HashComputeState, &Chunk) -> HashComputeState| {
+            let start = std::time::Instant::now();
+            let mut instance = HashComputeState::default();
+            for _ in 0..CHUNKS {
+                instance = compress(std::hint::black_box(instance), &chunk);
+            }
+            std::hint::black_box(instance);
+            let elapsed = start.elapsed().as_secs_f64();
+            CHUNKS as f64 * CHUNK_SIZE_BYTES as f64 / elapsed / (1024.0 * 1024.0)
+        };
+        let reference = measure(HashComputeState::process_chunk_reference);
+        let unrolled = measure(HashComputeState::process_chunk);
+        println!(
+            "Reference: {:.0} MiB/s, unrolled: {:.0} MiB/s",
+            reference, unrolled
+        );
+        assert!(unrolled > reference * 3.0);
+    }
 }
@@ -0,0 +1,304 @@
+use crate::encoding::{encode, Encoding};
+use crate::hash::Hash;
+
+use core::fmt::{Display, Formatter, LowerHex, Result, UpperHex};
+
+const SHORT_LENGTH: usize = 8;
+const COLON_SEPARATED_LENGTH: usize = 16 * 3 - 1;
+
+fn as_str(buffer: &[u8]) -> core::result::Result<&str, core::fmt::Error> {
+    core::str::from_utf8(buffer).map_err(|_| core::fmt::Error)
+}
+
+fn fmt_hex(hash: &Hash, formatter: &mut Formatter<'_>, uppercase: bool) -> Result {
+    let (mut buffer, length) = encode(hash.as_bytes(), Encoding::Hex);
+    if uppercase {
+        buffer.make_ascii_uppercase();
+    }
+    let length = formatter
+        .precision()
+        .map_or(length, |precision| precision.min(length));
+    formatter.pad_integral(true, "0x", as_str(&buffer[..length])?)
+}
+
+/// Formats the hash as lowercase hexadecimal.
+///
+/// The precision truncates the digits, the alternate flag prefixes them with `0x` and the width,
+/// fill and alignment work like with integers.
+///
+/// # Examples
+///
+/// ```
+/// use ya_md5::Md5Hasher;
+///
+/// let hash = Md5Hasher::hash_str("hello world");
+/// assert_eq!(format!("{:x}", hash), "5eb63bbbe01eeed093cb22bb8f5acdc3");
+/// assert_eq!(format!("{:#.8x}", hash), "0x5eb63bbb");
+/// ```
+impl LowerHex for Hash {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result {
+        fmt_hex(self, formatter, false)
+    }
+}
+
+/// Formats the hash as uppercase hexadecimal.
+///
+/// Supports the same flags as the [LowerHex] implementation.
+///
+/// # Examples
+///
+/// ```
+/// use ya_md5::Md5Hasher;
+///
+/// let hash = Md5Hasher::hash_str("hello world");
+/// assert_eq!(format!("{:X}", hash), "5EB63BBBE01EEED093CB22BB8F5ACDC3");
+/// assert_eq!(format!("{:#X}", hash), "0x5EB63BBBE01EEED093CB22BB8F5ACDC3");
+/// ```
+impl UpperHex for Hash {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result {
+        fmt_hex(self, formatter, true)
+    }
+}
+
+/// Formats the hash as lowercase hexadecimal.
+///
+/// The precision truncates the digits and the width, fill and alignment work like with strings;
+/// use the [LowerHex] implementation for the `0x` prefix.
+impl Display for Hash {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result {
+        let (buffer, length) = encode(self.as_bytes(), Encoding::Hex);
+        let length = formatter
+            .precision()
+            .map_or(length, |precision| precision.min(length));
+        formatter.pad(as_str(&buffer[..length])?)
+    }
+}
+
+/// Formats a [Hash](struct@Hash) with one of the supported [Encoding]s, returned by
+/// [Hash::encode].
+///
+/// The padding is included unless the alternate flag is used, the precision truncates the
+/// output and the width, fill and alignment work like with strings.
+#[derive(Debug, Copy, Clone)]
+pub struct EncodedHash<'a> {
+    hash: &'a Hash,
+    encoding: Encoding,
+}
+
+impl Display for EncodedHash<'_> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result {
+        let (buffer, mut length) = encode(self.hash.as_bytes(), self.encoding);
+        if formatter.alternate() {
+            length = buffer[..length]
+                .iter()
+                .position(|symbol| *symbol == b'=')
+                .unwrap_or(length);
+        }
+        formatter.pad(as_str(&buffer[..length])?)
+    }
+}
+
+/// Formats a [Hash](struct@Hash) as hexadecimal bytes separated by colons, returned by
+/// [Hash::colon_separated].
+///
+/// Uses lowercase digits unless formatted with [UpperHex]; the precision truncates the output
+/// and the width, fill and alignment work like with strings.
+#[derive(Debug, Copy, Clone)]
+pub struct ColonSeparatedHash<'a> {
+    hash: &'a Hash,
+}
+
+impl ColonSeparatedHash<'_> {
+    fn fmt_with_case(&self, formatter: &mut Formatter<'_>, uppercase: bool) -> Result {
+        let (hex, _) = encode(self.hash.as_bytes(), Encoding::Hex);
+        let mut buffer = [b':'; COLON_SEPARATED_LENGTH];
+        for (index, digits) in hex.chunks_exact(2).enumerate() {
+            buffer[index * 3..index * 3 + 2].copy_from_slice(digits);
+        }
+        if uppercase {
+            buffer.make_ascii_uppercase();
+        }
+        formatter.pad(as_str(&buffer)?)
+    }
+}
+
+impl Display for ColonSeparatedHash<'_> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result {
+        self.fmt_with_case(formatter, false)
+    }
+}
+
+impl LowerHex for ColonSeparatedHash<'_> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result {
+        self.fmt_with_case(formatter, false)
+    }
+}
+
+impl UpperHex for ColonSeparatedHash<'_> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result {
+        self.fmt_with_case(formatter, true)
+    }
+}
+
+/// Formats the first hexadecimal digits of a [Hash](struct@Hash), returned by
+/// [Hash::short].
+///
+/// Prints 8 digits by default, the precision can be used to change it; the rest of the flags
+/// work like in the [Display] implementation of [Hash](struct@Hash), or like in its [LowerHex]
+/// implementation when formatted as hexadecimal.
+#[derive(Debug, Copy, Clone)]
+pub struct ShortHash<'a> {
+    hash: &'a Hash,
+}
+
+impl ShortHash<'_> {
+    fn fmt_with_case(&self, formatter: &mut Formatter<'_>, uppercase: bool) -> Result {
+        let (mut buffer, length) = encode(self.hash.as_bytes(), Encoding::Hex);
+        if uppercase {
+            buffer.make_ascii_uppercase();
+        }
+        let length = formatter.precision().unwrap_or(SHORT_LENGTH).min(length);
+        formatter.pad_integral(true, "0x", as_str(&buffer[..length])?)
+    }
+}
+
+impl Display for ShortHash<'_> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result {
+        let (buffer, length) = encode(self.hash.as_bytes(), Encoding::Hex);
+        let length = formatter.precision().unwrap_or(SHORT_LENGTH).min(length);
+        formatter.pad(as_str(&buffer[..length])?)
+    }
+}
+
+impl LowerHex for ShortHash<'_> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result {
+        self.fmt_with_case(formatter, false)
+    }
+}
+
+impl UpperHex for ShortHash<'_> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result {
+        self.fmt_with_case(formatter, true)
+    }
+}
+
+impl Hash {
+    /// Returns an object that formats the hash with the given `encoding`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ya_md5::{Encoding, Md5Hasher};
+    ///
+    /// let hash = Md5Hasher::hash_str("hello world");
+    /// assert_eq!(hash.encode(Encoding::Base64).to_string(), "XrY7u+Ae7tCTyyK7j1rNww==");
+    /// assert_eq!(format!("{:#}", hash.encode(Encoding::Base64Url)), "XrY7u-Ae7tCTyyK7j1rNww");
+    /// ```
+    pub fn encode(&self, encoding: Encoding) -> EncodedHash<'_> {
+        EncodedHash {
+            hash: self,
+            encoding,
+        }
+    }
+
+    /// Returns an object that formats the hash as hexadecimal bytes separated by colons.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ya_md5::Md5Hasher;
+    ///
+    /// let hash = Md5Hasher::hash_str("hello world");
+    /// assert_eq!(
+    ///     format!("{:X}", hash.colon_separated()),
+    ///     "5E:B6:3B:BB:E0:1E:EE:D0:93:CB:22:BB:8F:5A:CD:C3"
+    /// );
+    /// ```
+    pub fn colon_separated(&self) -> ColonSeparatedHash<'_> {
+        ColonSeparatedHash { hash: self }
+    }
+
+    /// Returns an object that formats a truncated hexadecimal form of the hash, for log lines.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ya_md5::Md5Hasher;
+    ///
+    /// let hash = Md5Hasher::hash_str("hello world");
+    /// assert_eq!(hash.short().to_string(), "5eb63bbb");
+    /// assert_eq!(format!("{:.12}", hash.short()), "5eb63bbbe01e");
+    /// ```
+    pub fn short(&self) -> ShortHash<'_> {
+        ShortHash { hash: self }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Encoding, Hash};
+    use rstest::rstest;
+
+    #[ctor::ctor]
+    fn init() {
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    fn abc_hash() -> Hash {
+        "900150983cd24fb0d6963f7d28e17f72".parse().unwrap()
+    }
+
+    // Its short form has hex letters, unlike the one of "abc".
+    fn hello_world_hash() -> Hash {
+        "5eb63bbbe01eeed093cb22bb8f5acdc3".parse().unwrap()
+    }
+
+    #[rstest]
+    #[case(format!("{}", abc_hash()), "900150983cd24fb0d6963f7d28e17f72")]
+    #[case(format!("{:x}", abc_hash()), "900150983cd24fb0d6963f7d28e17f72")]
+    #[case(format!("{:X}", abc_hash()), "900150983CD24FB0D6963F7D28E17F72")]
+    #[case(format!("{:#x}", abc_hash()), "0x900150983cd24fb0d6963f7d28e17f72")]
+    #[case(format!("{:#X}", abc_hash()), "0x900150983CD24FB0D6963F7D28E17F72")]
+    #[case(format!("{:.8}", abc_hash()), "90015098")]
+    #[case(format!("{:#.4X}", abc_hash()), "0x9001")]
+    #[case(format!("{:>10.4}", abc_hash()), "      9001")]
+    #[case(format!("{:#010.4x}", abc_hash()), "0x00009001")]
+    #[case(format!("{:+}", abc_hash()), "900150983cd24fb0d6963f7d28e17f72")]
+    #[case(format!("{:*<34}", abc_hash()), "900150983cd24fb0d6963f7d28e17f72**")]
+    fn test_hex(#[case] result: String, #[case] expected: &str) {
+        assert_eq!(result, expected);
+    }
+
+    #[rstest]
+    fn test_display_alternate() {
+        assert_eq!(format!("{:#}", abc_hash()), format!("{}", abc_hash()));
+    }
+
+    #[rstest]
+    #[case(format!("{}", abc_hash().encode(Encoding::Hex)), "900150983cd24fb0d6963f7d28e17f72")]
+    #[case(format!("{}", abc_hash().encode(Encoding::Base64)), "kAFQmDzST7DWlj99KOF/cg==")]
+    #[case(format!("{:#}", abc_hash().encode(Encoding::Base64)), "kAFQmDzST7DWlj99KOF/cg")]
+    #[case(format!("{}", abc_hash().encode(Encoding::Base64Url)), "kAFQmDzST7DWlj99KOF_cg==")]
+    #[case(format!("{:#}", abc_hash().encode(Encoding::Base64Url)), "kAFQmDzST7DWlj99KOF_cg")]
+    #[case(format!("{}", abc_hash().encode(Encoding::Base32)), "SAAVBGB42JH3BVUWH56SRYL7OI======")]
+    #[case(format!("{:#}", abc_hash().encode(Encoding::Base32)), "SAAVBGB42JH3BVUWH56SRYL7OI")]
+    #[case(format!("{:.6}", abc_hash().encode(Encoding::Base64)), "kAFQmD")]
+    #[case(format!("{:*^26}", abc_hash().encode(Encoding::Base64)), "*kAFQmDzST7DWlj99KOF/cg==*")]
+    fn test_encode(#[case] result: String, #[case] expected: &str) {
+        assert_eq!(result, expected);
+    }
+
+    #[rstest]
+    #[case(format!("{}", abc_hash().colon_separated()), "90:01:50:98:3c:d2:4f:b0:d6:96:3f:7d:28:e1:7f:72")]
+    #[case(format!("{:X}", abc_hash().colon_separated()), "90:01:50:98:3C:D2:4F:B0:D6:96:3F:7D:28:E1:7F:72")]
+    #[case(format!("{:.8}", abc_hash().colon_separated()), "90:01:50")]
+    #[case(format!("{}", abc_hash().short()), "90015098")]
+    #[case(format!("{}", hello_world_hash().short()), "5eb63bbb")]
+    #[case(format!("{:X}", hello_world_hash().short()), "5EB63BBB")]
+    #[case(format!("{:.4}", abc_hash().short()), "9001")]
+    #[case(format!("{:#.40}", abc_hash().short()), "900150983cd24fb0d6963f7d28e17f72")]
+    #[case(format!("{:#.40x}", abc_hash().short()), "0x900150983cd24fb0d6963f7d28e17f72")]
+    fn test_other_formats(#[case] result: String, #[case] expected: &str) {
+        assert_eq!(result, expected);
+    }
+}
@@ -0,0 +1,215 @@
+use crate::hash::Hash;
+use crate::{Md5Hasher, DEFAULT_READ_BUFFER_SIZE};
+
+use std::io::{BufRead, Read};
+
+/// A [Read] adapter that computes the MD5 hash of every byte read through it.
+///
+/// Allows to consume a stream and compute its hash in the same pass. When the inner reader
+/// implements [BufRead] the adapter implements it too, hashing the bytes as they are consumed;
+/// [BufRead::fill_buf] returns a copy of at most [DEFAULT_READ_BUFFER_SIZE] bytes of the inner
+/// buffer, so the bytes consumed are hashed without reading them again.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{Cursor, Read};
+/// use ya_md5::HashingReader;
+///
+/// let mut reader = HashingReader::new(Cursor::new("hello world"));
+/// let mut content = String::new();
+/// reader.read_to_string(&mut content).expect("Unexpected error reading from a cursor");
+/// let (_, hash, length) = reader.into_parts();
+/// assert_eq!(content, "hello world");
+/// assert_eq!(format!("{}", hash), "5eb63bbbe01eeed093cb22bb8f5acdc3");
+/// assert_eq!(length, 11);
+/// ```
+pub struct HashingReader<R> {
+    inner: R,
+    hasher: Md5Hasher,
+    length: u64,
+    // Copy of the start of the inner buffer returned by the last `fill_buf`, consumed up to
+    // `position`.
+    buffer: Vec<u8>,
+    position: usize,
+}
+
+impl<R: Read> HashingReader<R> {
+    /// Creates an adapter that reads from `inner`.
+    pub fn new(inner: R) -> Self {
+        HashingReader {
+            inner,
+            hasher: Md5Hasher::default(),
+            length: 0,
+            buffer: Vec::new(),
+            position: 0,
+        }
+    }
+
+    /// Returns a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns the number of bytes read so far.
+    pub fn bytes_read(&self) -> u64 {
+        self.length
+    }
+
+    /// Consumes the adapter, returning the inner reader, the hash of the bytes read and the
+    /// number of them.
+    pub fn into_parts(self) -> (R, Hash, u64) {
+        (self.inner, self.hasher.finalize(), self.length)
+    }
+
+    fn process(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+        self.length += data.len() as u64;
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        // The bytes not consumed yet are read again from the inner reader.
+        self.buffer.clear();
+        self.position = 0;
+        let readed = self.inner.read(buf)?;
+        self.process(&buf[..readed]);
+        Ok(readed)
+    }
+}
+
+impl<R: BufRead> BufRead for HashingReader<R> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        if self.position == self.buffer.len() {
+            let available = self.inner.fill_buf()?;
+            let length = available.len().min(DEFAULT_READ_BUFFER_SIZE);
+            self.buffer.clear();
+            self.buffer.extend_from_slice(&available[..length]);
+            self.position = 0;
+        }
+        Ok(&self.buffer[self.position..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        let end = self.position + amt.min(self.buffer.len() - self.position);
+        self.hasher.update(&self.buffer[self.position..end]);
+        self.length += (end - self.position) as u64;
+        self.inner.consume(end - self.position);
+        self.position = end;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::HashingReader;
+    use rstest::rstest;
+    use std::io::{BufRead, BufReader, Cursor, Error, Read};
+
+    #[ctor::ctor]
+    fn init() {
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    const DATA: &str =
+        "12345678901234567890123456789012345678901234567890123456789012345678901234567890";
+    const EXPECTED: &str = "57edf4a22be3c955ac49da2e2107b67a";
+
+    #[rstest]
+    #[case(1)]
+    #[case(7)]
+    #[case(64)]
+    #[case(1024)]
+    fn test_read(#[case] buffer_size: usize) {
+        let mut reader = HashingReader::new(Cursor::new(DATA));
+        let mut buffer = vec![0; buffer_size];
+        let mut content = Vec::new();
+        loop {
+            let readed = reader.read(&mut buffer).unwrap();
+            if readed == 0 {
+                break;
+            }
+            content.extend_from_slice(&buffer[..readed]);
+        }
+        assert_eq!(reader.bytes_read(), DATA.len() as u64);
+        let (_, hash, length) = reader.into_parts();
+        assert_eq!(content, DATA.as_bytes());
+        assert_eq!(hash, EXPECTED);
+        assert_eq!(length, DATA.len() as u64);
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(7)]
+    #[case(64)]
+    fn test_buf_read(#[case] capacity: usize) {
+        let mut reader = HashingReader::new(BufReader::with_capacity(capacity, Cursor::new(DATA)));
+        let mut lines = Vec::new();
+        let mut line = String::new();
+        while reader.read_line(&mut line).unwrap() > 0 {
+            lines.push(std::mem::take(&mut line));
+        }
+        let (_, hash, length) = reader.into_parts();
+        assert_eq!(lines, [DATA]);
+        assert_eq!(hash, EXPECTED);
+        assert_eq!(length, DATA.len() as u64);
+    }
+
+    #[rstest]
+    fn test_partial_consume() {
+        let mut reader = HashingReader::new(Cursor::new(DATA));
+        let available = reader.fill_buf().unwrap().len();
+        assert_eq!(available, DATA.len());
+        reader.consume(10);
+        let (_, hash, length) = reader.into_parts();
+        assert_eq!(hash, "e807f1fcf82d132f9bb018ca6738a19f");
+        assert_eq!(length, 10);
+    }
+
+    // Fails every other call to `fill_buf`, which must not lose the bytes consumed.
+    struct FlakyReader {
+        inner: Cursor<&'static str>,
+        fail: bool,
+    }
+
+    impl Read for FlakyReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.inner.read(buf)
+        }
+    }
+
+    impl BufRead for FlakyReader {
+        fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+            self.fail = !self.fail;
+            if self.fail {
+                return Err(Error::other("flaky"));
+            }
+            self.inner.fill_buf()
+        }
+
+        fn consume(&mut self, amt: usize) {
+            self.inner.consume(amt);
+        }
+    }
+
+    #[rstest]
+    fn test_consume_after_failed_fill_buf() {
+        let mut reader = HashingReader::new(FlakyReader {
+            inner: Cursor::new(DATA),
+            fail: false,
+        });
+        loop {
+            let available = match reader.fill_buf() {
+                Ok(buffer) => buffer.len(),
+                Err(_) => continue,
+            };
+            if available == 0 {
+                break;
+            }
+            reader.consume(available.min(7));
+        }
+        let (_, hash, length) = reader.into_parts();
+        assert_eq!(hash, EXPECTED);
+        assert_eq!(length, DATA.len() as u64);
+    }
+}
@@ -0,0 +1,183 @@
+use crate::hash::Hash;
+use crate::Md5Hasher;
+
+use std::io::{IoSlice, Write};
+
+/// A [Write] adapter that computes the MD5 hash of every byte written through it.
+///
+/// Only the bytes accepted by the inner writer are hashed, so short writes don't corrupt the
+/// hash.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Write;
+/// use ya_md5::HashingWriter;
+///
+/// let mut writer = HashingWriter::new(Vec::new());
+/// write!(writer, "hello world").expect("Unexpected error writing to a vector");
+/// let (content, hash, length) = writer.into_parts();
+/// assert_eq!(content, b"hello world");
+/// assert_eq!(format!("{}", hash), "5eb63bbbe01eeed093cb22bb8f5acdc3");
+/// assert_eq!(length, 11);
+/// ```
+pub struct HashingWriter<W> {
+    inner: W,
+    hasher: Md5Hasher,
+    length: u64,
+}
+
+impl<W: Write> HashingWriter<W> {
+    /// Creates an adapter that writes to `inner`.
+    pub fn new(inner: W) -> Self {
+        HashingWriter {
+            inner,
+            hasher: Md5Hasher::default(),
+            length: 0,
+        }
+    }
+
+    /// Returns a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns the number of bytes written so far.
+    pub fn bytes_written(&self) -> u64 {
+        self.length
+    }
+
+    /// Consumes the adapter, returning the inner writer, the hash of the bytes written and the
+    /// number of them.
+    ///
+    /// The inner writer is not flushed.
+    pub fn into_parts(self) -> (W, Hash, u64) {
+        (self.inner, self.hasher.finalize(), self.length)
+    }
+
+    fn process(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+        self.length += data.len() as u64;
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.process(&buf[..written]);
+        Ok(written)
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> std::io::Result<usize> {
+        let written = self.inner.write_vectored(bufs)?;
+        let mut remaining = written;
+        for buf in bufs {
+            if remaining == 0 {
+                break;
+            }
+            let accepted = remaining.min(buf.len());
+            self.process(&buf[..accepted]);
+            remaining -= accepted;
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::HashingWriter;
+    use rstest::rstest;
+    use std::io::{IoSlice, Write};
+
+    #[ctor::ctor]
+    fn init() {
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    // A writer that accepts at most `limit` bytes on each call.
+    struct ShortWriter {
+        content: Vec<u8>,
+        limit: usize,
+    }
+
+    impl Write for ShortWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let accepted = buf.len().min(self.limit);
+            self.content.extend_from_slice(&buf[..accepted]);
+            Ok(accepted)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    const DATA: &str =
+        "12345678901234567890123456789012345678901234567890123456789012345678901234567890";
+    const EXPECTED: &str = "57edf4a22be3c955ac49da2e2107b67a";
+
+    #[rstest]
+    #[case(1)]
+    #[case(7)]
+    #[case(1024)]
+    fn test_write_all(#[case] limit: usize) {
+        let mut writer = HashingWriter::new(ShortWriter {
+            content: Vec::new(),
+            limit,
+        });
+        writer.write_all(DATA.as_bytes()).unwrap();
+        writer.flush().unwrap();
+        assert_eq!(writer.bytes_written(), DATA.len() as u64);
+        let (inner, hash, length) = writer.into_parts();
+        assert_eq!(inner.content, DATA.as_bytes());
+        assert_eq!(hash, EXPECTED);
+        assert_eq!(length, DATA.len() as u64);
+    }
+
+    #[rstest]
+    fn test_short_write() {
+        let mut writer = HashingWriter::new(ShortWriter {
+            content: Vec::new(),
+            limit: 10,
+        });
+        assert_eq!(writer.write(DATA.as_bytes()).unwrap(), 10);
+        let (_, hash, length) = writer.into_parts();
+        assert_eq!(hash, "e807f1fcf82d132f9bb018ca6738a19f");
+        assert_eq!(length, 10);
+    }
+
+    #[rstest]
+    fn test_write_vectored() {
+        let (left, right) = DATA.split_at(30);
+        let mut writer = HashingWriter::new(Vec::new());
+        let written = writer
+            .write_vectored(&[
+                IoSlice::new(left.as_bytes()),
+                IoSlice::new(right.as_bytes()),
+            ])
+            .unwrap();
+        assert_eq!(written, DATA.len());
+        let (content, hash, _) = writer.into_parts();
+        assert_eq!(content, DATA.as_bytes());
+        assert_eq!(hash, EXPECTED);
+    }
+
+    #[rstest]
+    fn test_short_write_vectored() {
+        let mut writer = HashingWriter::new(ShortWriter {
+            content: Vec::new(),
+            limit: 10,
+        });
+        // The default implementation writes only the first non-empty buffer.
+        let written = writer
+            .write_vectored(&[IoSlice::new(&[]), IoSlice::new(DATA.as_bytes())])
+            .unwrap();
+        assert_eq!(written, 10);
+        let (_, hash, _) = writer.into_parts();
+        assert_eq!(hash, "e807f1fcf82d132f9bb018ca6738a19f");
+    }
+}
@@ -0,0 +1,273 @@
+use crate::chunk::CHUNK_SIZE_BYTES;
+use crate::hash::{ct_eq_bytes, Hash};
+use crate::md5_error::Md5Error;
+use crate::Md5Hasher;
+
+#[cfg(feature = "std")]
+use std::io::Read;
+
+const DIGEST_SIZE_BYTES: usize = 16;
+const INNER_PAD: u8 = 0x36;
+const OUTER_PAD: u8 = 0x5c;
+// Minimum length of a truncated code, half of the digest and at least 80 bits as recommended by
+// RFC 2104.
+const MIN_TRUNCATED_SIZE_BYTES: usize = 10;
+
+/// Computes the HMAC-MD5 authentication code of a message, as defined in [RFC 2104].
+///
+/// The code is returned as a [Hash](struct@Hash), so it can be formatted and compared like one;
+/// [HmacMd5::finalize_truncated] returns its first bytes for truncated variants like
+/// HMAC-MD5-96.
+///
+/// # Examples
+///
+/// ```
+/// use ya_md5::HmacMd5;
+///
+/// let mut hmac = HmacMd5::new("Jefe");
+/// hmac.update("what do ya want ");
+/// hmac.update("for nothing?");
+/// let result = format!("{}", hmac.finalize());
+/// assert_eq!(result, "750c783e6ab0b503eaa86e310a5db738");
+/// ```
+///
+/// [RFC 2104]: https://www.rfc-editor.org/rfc/rfc2104
+#[derive(Clone)]
+pub struct HmacMd5 {
+    inner: Md5Hasher,
+    outer: Md5Hasher,
+}
+
+impl HmacMd5 {
+    /// Creates an instance that authenticates messages with the `key`.
+    ///
+    /// Keys longer than a chunk of 64 bytes are hashed first, as required by the RFC.
+    pub fn new(key: impl AsRef<[u8]>) -> HmacMd5 {
+        let key = key.as_ref();
+        let mut block = [0_u8; CHUNK_SIZE_BYTES];
+        if key.len() > CHUNK_SIZE_BYTES {
+            block[..DIGEST_SIZE_BYTES].copy_from_slice(Md5Hasher::hash_slice(key).as_bytes());
+        } else {
+            block[..key.len()].copy_from_slice(key);
+        }
+        let mut inner = Md5Hasher::default();
+        inner.update(block.map(|byte| byte ^ INNER_PAD));
+        let mut outer = Md5Hasher::default();
+        outer.update(block.map(|byte| byte ^ OUTER_PAD));
+        HmacMd5 { inner, outer }
+    }
+
+    /// Computes and returns the authentication code of the data that can be readed from the
+    /// `input` with the `key`.
+    ///
+    /// # Errors
+    ///
+    /// Works like [Md5Hasher::hash].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use ya_md5::HmacMd5;
+    ///
+    /// let code = HmacMd5::hash("Jefe", &mut Cursor::new("what do ya want for nothing?"))
+    ///     .expect("Unexpected error reading from a cursor");
+    /// let result = format!("{}", code);
+    /// assert_eq!(result, "750c783e6ab0b503eaa86e310a5db738");
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn hash<R: Read + ?Sized>(key: impl AsRef<[u8]>, input: &mut R) -> Result<Hash, Md5Error> {
+        let mut hmac = HmacMd5::new(key);
+        hmac.update_reader(input)?;
+        Ok(hmac.finalize())
+    }
+
+    /// Process the `data` as part of the message.
+    pub fn update(&mut self, data: impl AsRef<[u8]>) {
+        self.inner.update(data);
+    }
+
+    /// Process all the data that can be readed from the `input` as part of the message, returning
+    /// the number of bytes readed.
+    ///
+    /// # Errors
+    ///
+    /// Works like [Md5Hasher::update_reader], including resuming after an error.
+    #[cfg(feature = "std")]
+    pub fn update_reader<R: Read + ?Sized>(&mut self, input: &mut R) -> Result<u64, Md5Error> {
+        self.inner.update_reader(input)
+    }
+
+    /// Computes the authentication code of the message, consuming the instance in the process.
+    pub fn finalize(self) -> Hash {
+        let mut outer = self.outer;
+        outer.update(self.inner.finalize().as_bytes());
+        outer.finalize()
+    }
+
+    /// Computes the authentication code of the message truncated to its first `N` bytes, like the
+    /// 12 bytes of HMAC-MD5-96; `N` can't be lower than 10, the minimum recommended by the RFC,
+    /// nor greater than 16.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ya_md5::HmacMd5;
+    ///
+    /// let mut hmac = HmacMd5::new([0x0c; 16]);
+    /// hmac.update("Test With Truncation");
+    /// let code: [u8; 12] = hmac.finalize_truncated();
+    /// assert_eq!(code[..4], [0x56, 0x46, 0x1e, 0xf2]);
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use ya_md5::HmacMd5;
+    ///
+    /// let code: [u8; 8] = HmacMd5::new([0x0c; 16]).finalize_truncated();
+    /// ```
+    pub fn finalize_truncated<const N: usize>(self) -> [u8; N] {
+        const {
+            assert!(
+                N >= MIN_TRUNCATED_SIZE_BYTES,
+                "The code can't be truncated below 10 bytes"
+            );
+            assert!(N <= DIGEST_SIZE_BYTES, "The code has only 16 bytes");
+        };
+        let mut result = [0; N];
+        result.copy_from_slice(&self.finalize().as_bytes()[..N]);
+        result
+    }
+
+    /// Computes the authentication code of the message, consuming the instance in the process,
+    /// and compares it in constant time with the `expected` one.
+    ///
+    /// # Errors
+    ///
+    /// If the codes are different a [Md5Error::TagMismatch] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ya_md5::{Hash, HmacMd5};
+    ///
+    /// let expected: Hash = "750c783e6ab0b503eaa86e310a5db738".parse().expect("Invalid digest");
+    /// let mut hmac = HmacMd5::new("Jefe");
+    /// hmac.update("what do ya want for nothing?");
+    /// assert!(hmac.verify(&expected).is_ok());
+    /// ```
+    pub fn verify(self, expected: &Hash) -> Result<(), Md5Error> {
+        self.verify_truncated(expected.as_bytes())
+    }
+
+    /// Computes the authentication code of the message, consuming the instance in the process,
+    /// and compares its first bytes in constant time with the `expected` truncated code.
+    ///
+    /// # Errors
+    ///
+    /// If the `expected` code is shorter than 10 bytes, the minimum recommended by the RFC, or
+    /// longer than 16 a [Md5Error::InvalidTagLength] is returned; if the codes are different a
+    /// [Md5Error::TagMismatch] is returned.
+    pub fn verify_truncated(self, expected: &[u8]) -> Result<(), Md5Error> {
+        let length = expected.len();
+        if !(MIN_TRUNCATED_SIZE_BYTES..=DIGEST_SIZE_BYTES).contains(&length) {
+            return Err(Md5Error::InvalidTagLength { length });
+        }
+        if ct_eq_bytes(&self.finalize().as_bytes()[..length], expected) {
+            Ok(())
+        } else {
+            Err(Md5Error::TagMismatch)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::HmacMd5;
+    use crate::Md5Error;
+    use rstest::rstest;
+
+    #[ctor::ctor]
+    fn init() {
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    // Values taken from RFC 2202 section "2. Test Cases for HMAC-MD5"
+    // https://www.rfc-editor.org/rfc/rfc2202
+    #[rstest]
+    #[case(vec![0x0b; 16], b"Hi There".to_vec(), "9294727a3638bb1c13f48ef8158bfc9d")]
+    #[case(
+        b"Jefe".to_vec(),
+        b"what do ya want for nothing?".to_vec(),
+        "750c783e6ab0b503eaa86e310a5db738"
+    )]
+    #[case(vec![0xaa; 16], vec![0xdd; 50], "56be34521d144c88dbb8c733f0e8b3f6")]
+    #[case((1..=25).collect(), vec![0xcd; 50], "697eaf0aca3a3aea3a75164746ffaa79")]
+    #[case(
+        vec![0x0c; 16],
+        b"Test With Truncation".to_vec(),
+        "56461ef2342edc00f9bab995690efd4c"
+    )]
+    #[case(
+        vec![0xaa; 80],
+        b"Test Using Larger Than Block-Size Key - Hash Key First".to_vec(),
+        "6b1ab7fe4bd7bf8f0b62e6ce61b9d0cd"
+    )]
+    #[case(
+        vec![0xaa; 80],
+        b"Test Using Larger Than Block-Size Key and Larger Than One Block-Size Data".to_vec(),
+        "6f630fad67cda0ee1fb1f562db3aa53e"
+    )]
+    fn test_rfc_2202(#[case] key: Vec<u8>, #[case] data: Vec<u8>, #[case] expected: &str) {
+        let mut hmac = HmacMd5::new(&key);
+        hmac.update(&data);
+        assert_eq!(hmac.finalize(), expected);
+        let mut hmac = HmacMd5::new(&key);
+        hmac.update(&data);
+        assert!(hmac.verify(&expected.parse().unwrap()).is_ok());
+    }
+
+    #[rstest]
+    fn test_truncated() {
+        // RFC 2202 test case 5, digest-96.
+        let expected = [
+            0x56, 0x46, 0x1e, 0xf2, 0x34, 0x2e, 0xdc, 0x00, 0xf9, 0xba, 0xb9, 0x95,
+        ];
+        let mut hmac = HmacMd5::new([0x0c; 16]);
+        hmac.update("Test With Truncation");
+        assert_eq!(hmac.clone().finalize_truncated::<12>(), expected);
+        assert!(hmac.verify_truncated(&expected).is_ok());
+    }
+
+    #[rstest]
+    #[case(&[0x56, 0x46, 0x1e, 0xf2, 0x34, 0x2e, 0xdc, 0x00, 0xf9, 0xba, 0xb9, 0x96])]
+    #[case(&[0; 16])]
+    fn test_verify_mismatch(#[case] expected: &[u8]) {
+        let mut hmac = HmacMd5::new([0x0c; 16]);
+        hmac.update("Test With Truncation");
+        assert!(matches!(
+            hmac.verify_truncated(expected),
+            Err(Md5Error::TagMismatch)
+        ));
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(9)]
+    #[case(17)]
+    fn test_verify_invalid_length(#[case] length: usize) {
+        let hmac = HmacMd5::new("key");
+        let expected = vec![0; length];
+        assert!(matches!(
+            hmac.verify_truncated(&expected),
+            Err(Md5Error::InvalidTagLength { length: error_length }) if error_length == length
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[rstest]
+    fn test_hash_reader() {
+        let mut input = std::io::Cursor::new(vec![0xdd; 50]);
+        let code = HmacMd5::hash([0xaa; 16], &mut input).unwrap();
+        assert_eq!(code, "56be34521d144c88dbb8c733f0e8b3f6");
+    }
+}
@@ -1,10 +1,12 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![warn(missing_docs)]
 
 //! An implementation of the [MD5] hash algorithm capable to hash data readed from a
 //! [std::io::Read] implementation.
 //!
 //! ## Example
-//! ```rust
+#![cfg_attr(feature = "std", doc = "```rust")]
+#![cfg_attr(not(feature = "std"), doc = "```rust,ignore")]
 //! use std::fs::File;
 //! use std::io::prelude::*;
 //! use ya_md5::Md5Hasher;
@@ -24,23 +26,107 @@
 //! }
 //! ```
 //!
+//! ## Features
+//!
+//! - `std` (default): hashing of [std::io::Read] objects and the other I/O functions, and
+//!   parallel and multi-buffer hashing. Without it the crate is `no_std` and only needs `core`.
+//! - `alloc` (enabled by `std`): the functions that return a `Vec`, like
+//!   [Md5Hasher::export_state], for `no_std` targets with an allocator.
+//! - `serde`: implements `Serialize` and `Deserialize` for [Hash](struct@Hash) and
+//!   [Md5Hasher], the latter using the blob of [Md5Hasher::export_state].
+//! - `tokio`: asynchronous hashing of `tokio` I/O objects, see the `tokio` module.
+//! - `futures`: runtime-agnostic asynchronous hashing of `futures` I/O objects and streams, see
+//!   the `futures` module.
+//! - `rayon`: parallel hashing of many inputs in the rayon thread pool, see the `rayon` module.
+//!
 //! [MD5]: https://en.wikipedia.org/wiki/MD5
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "std")]
+mod batch;
 mod chunk;
 mod chunk_processor;
+mod compression_function;
 mod conversions;
+mod encoding;
+#[cfg(feature = "futures")]
+pub mod futures;
 mod hash;
 mod hash_compute_state;
+mod hash_format;
+#[cfg(feature = "std")]
+mod hashing_reader;
+#[cfg(feature = "std")]
+mod hashing_writer;
+mod hmac;
+mod macros;
+mod md4;
 mod md5_error;
+#[cfg(feature = "std")]
+mod multi_buffer;
+#[cfg(feature = "rayon")]
+pub mod rayon;
+mod saved_state;
+#[cfg(feature = "serde")]
+mod serde_impls;
+#[cfg(feature = "tokio")]
+pub mod tokio;
 
-use chunk::CHUNK_SIZE_BYTES;
-
+pub use crate::chunk_processor::ChunkProcessor;
+pub use crate::compression_function::{CompressionFunction, Endianness};
+pub use crate::encoding::Encoding;
 pub use crate::hash::Hash;
+pub use crate::hash_compute_state::HashComputeState;
+pub use crate::hash_format::{ColonSeparatedHash, EncodedHash, ShortHash};
+#[cfg(feature = "std")]
+pub use crate::hashing_reader::HashingReader;
+#[cfg(feature = "std")]
+pub use crate::hashing_writer::HashingWriter;
+pub use crate::hmac::HmacMd5;
+pub use crate::md4::Md4Hasher;
 pub use crate::md5_error::Md5Error;
 
-use crate::chunk_processor::ChunkProcessor;
+use crate::chunk_processor::{hash_const, padding};
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::io::{BufRead, ErrorKind, IoSlice, Read, Write};
+
+/// Size of the buffer used by [Md5Hasher::hash] and [Md5Hasher::update_reader].
+#[cfg(feature = "std")]
+pub const DEFAULT_READ_BUFFER_SIZE: usize = 8 * 1024;
 
-use std::io::Read;
+#[cfg(feature = "std")]
+fn read_error(source: std::io::Error, bytes_read: u64) -> Md5Error {
+    Md5Error::ReadError { source, bytes_read }
+}
+
+// Reads the `input` until its end through the `buffer`, passing the readed data to `update` and
+// returning the number of bytes readed.
+#[cfg(feature = "std")]
+pub(crate) fn read_chunks<R: Read + ?Sized>(
+    input: &mut R,
+    buffer: &mut [u8],
+    mut update: impl FnMut(&[u8]),
+) -> Result<u64, Md5Error> {
+    let mut total = 0;
+    loop {
+        let readed = match input.read(buffer) {
+            Ok(readed) => readed,
+            Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+            Err(error) => return Err(read_error(error, total)),
+        };
+        if readed == 0 {
+            break;
+        }
+        update(&buffer[..readed]);
+        total += readed as u64;
+    }
+    Ok(total)
+}
 
 /// A hasher thath computes the MD5 hash of a given list of chunks.
 ///
@@ -48,17 +134,40 @@ use std::io::Read;
 ///
 /// Provides conveniente functions to compute the MD5 hash of various sources without having to
 /// create and manage an instance.
-#[derive(Default)]
+///
+/// Cloning an instance is cheap, it only copies the internal state and the data not yet
+/// processed, so a shared prefix can be hashed once and then forked for each suffix.
+///
+/// # Examples
+///
+/// ```
+/// use ya_md5::Md5Hasher;
+///
+/// let mut prefix = Md5Hasher::default();
+/// prefix.update("hello ");
+/// let mut world = prefix.clone();
+/// world.update("world");
+/// let mut there = prefix;
+/// there.update("there");
+/// assert_eq!(format!("{}", world.finalize()), "5eb63bbbe01eeed093cb22bb8f5acdc3");
+/// assert_eq!(format!("{}", there.finalize()), "161bc25962da8fed6d2f59922fb642aa");
+/// ```
+#[derive(Default, Clone)]
 pub struct Md5Hasher {
     processor: ChunkProcessor,
 }
 
 impl Md5Hasher {
+    #[cfg(feature = "std")]
     /// Computes and returns the hash of the data that can be readed from the `input`.
     ///
+    /// Reads interrupted by a signal are retried.
+    ///
     /// # Errors
     ///
-    /// If there's any I/O error while reading the `input` an error is returned.
+    /// If there's any I/O error while reading the `input` a [Md5Error::ReadError] is returned
+    /// with the number of bytes readed before it; use [Md5Hasher::update_reader] to be able to
+    /// resume the hashing after an error.
     ///
     /// # Examples
     ///
@@ -71,16 +180,64 @@ impl Md5Hasher {
     /// let result = format!("{}", hash);
     /// assert_eq!(result, "5eb63bbbe01eeed093cb22bb8f5acdc3");
     /// ```
-    pub fn hash(input: &mut dyn Read) -> Result<Hash, Md5Error> {
+    pub fn hash<R: Read + ?Sized>(input: &mut R) -> Result<Hash, Md5Error> {
         let mut hasher = Self::default();
-        let mut buffer = [0; CHUNK_SIZE_BYTES];
-        loop {
-            let readed = input.read(&mut buffer).map_err(Md5Error::from)?;
-            if readed == 0 {
-                break;
-            }
-            hasher.update(&buffer[..readed]);
-        }
+        hasher.update_reader(input)?;
+        Ok(hasher.finalize())
+    }
+
+    #[cfg(feature = "std")]
+    /// Computes and returns the hash of the data that can be readed from the `input`, reading
+    /// it in blocks of `buffer_size` bytes.
+    ///
+    /// A `buffer_size` of 0 is treated as 1.
+    ///
+    /// # Errors
+    ///
+    /// Works like [Md5Hasher::hash].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use ya_md5::Md5Hasher;
+    ///
+    /// let hash = Md5Hasher::hash_with_buffer_size(&mut Cursor::new("hello world"), 64 * 1024)
+    ///     .expect("Unexpected error reading from a cursor");
+    /// let result = format!("{}", hash);
+    /// assert_eq!(result, "5eb63bbbe01eeed093cb22bb8f5acdc3");
+    /// ```
+    pub fn hash_with_buffer_size<R: Read + ?Sized>(
+        input: &mut R,
+        buffer_size: usize,
+    ) -> Result<Hash, Md5Error> {
+        let mut hasher = Self::default();
+        hasher.update_reader_with_buffer(input, &mut vec![0; buffer_size.max(1)])?;
+        Ok(hasher.finalize())
+    }
+
+    #[cfg(feature = "std")]
+    /// Computes and returns the hash of the data that can be readed from the `input`, hashing it
+    /// directly from the buffer of the `input` without copying it.
+    ///
+    /// # Errors
+    ///
+    /// Works like [Md5Hasher::hash].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{BufReader, Cursor};
+    /// use ya_md5::Md5Hasher;
+    ///
+    /// let hash = Md5Hasher::hash_buf_read(&mut BufReader::new(Cursor::new("hello world")))
+    ///     .expect("Unexpected error reading from a cursor");
+    /// let result = format!("{}", hash);
+    /// assert_eq!(result, "5eb63bbbe01eeed093cb22bb8f5acdc3");
+    /// ```
+    pub fn hash_buf_read<R: BufRead + ?Sized>(input: &mut R) -> Result<Hash, Md5Error> {
+        let mut hasher = Self::default();
+        hasher.update_buf_read(input)?;
         Ok(hasher.finalize())
     }
 
@@ -100,6 +257,7 @@ impl Md5Hasher {
         hasher.finalize()
     }
 
+    #[cfg(feature = "alloc")]
     /// Computes and returns the hash of the data in the `Vec`.
     ///
     /// # Examples
@@ -129,14 +287,333 @@ impl Md5Hasher {
         Self::hash_slice(data.as_bytes())
     }
 
+    /// Computes and returns the hash of the data in the slice in const context, so the digests of
+    /// known data can be embedded as constants; see also the [md5!] macro.
+    ///
+    /// It's much slower than [Md5Hasher::hash_slice] at runtime.
+    ///
+    /// # Examples
+    /// ```
+    /// use ya_md5::{Hash, Md5Hasher};
+    ///
+    /// const HASH: Hash = Md5Hasher::hash_const(b"hello world");
+    /// let result = format!("{}", HASH);
+    /// assert_eq!(result, "5eb63bbbe01eeed093cb22bb8f5acdc3");
+    /// ```
+    pub const fn hash_const(data: &[u8]) -> Hash {
+        hash_const(data)
+    }
+
     /// Process a single chunk and use it to compute the internal state.
     pub fn update(&mut self, data: impl AsRef<[u8]>) {
         self.processor.update(data);
     }
 
+    /// Process the first `bit_length` bits of the `data`, for messages whose length is not a
+    /// whole number of bytes.
+    ///
+    /// The bits of each byte are taken from the most significant one, so the last byte holds the
+    /// remaining bits in its high part. The data can be mixed with other calls to
+    /// [Md5Hasher::update] and this function, although every byte is processed bit by bit while
+    /// the length is not a whole number of bytes.
+    ///
+    /// # Panics
+    ///
+    /// If `bit_length` is greater than the number of bits in `data`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ya_md5::Md5Hasher;
+    ///
+    /// // The 5 bits message 01101.
+    /// let mut hasher = Md5Hasher::default();
+    /// hasher.update_bits(&[0b0110_1000], 5);
+    /// let mut other = Md5Hasher::default();
+    /// other.update_bits(&[0b0110_1111], 5);
+    /// assert_eq!(hasher.finalize(), other.finalize());
+    /// ```
+    pub fn update_bits(&mut self, data: &[u8], bit_length: usize) {
+        self.processor.update_bits(data, bit_length);
+    }
+
+    #[cfg(feature = "std")]
+    /// Process all the data that can be readed from the `input`, returning the number of bytes
+    /// readed.
+    ///
+    /// The data is processed after the one already in the instance, so it can be mixed with
+    /// other calls to [Md5Hasher::update].
+    ///
+    /// Reads interrupted by a signal are retried.
+    ///
+    /// # Errors
+    ///
+    /// If there's any I/O error while reading the `input` a [Md5Error::ReadError] is returned
+    /// with the number of bytes readed before it.
+    ///
+    /// The instance keeps every byte readed before the error, so the hashing can be resumed by
+    /// calling this function again once the `input` is ready, instead of starting over.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use ya_md5::Md5Hasher;
+    ///
+    /// let mut hasher = Md5Hasher::default();
+    /// hasher.update("hello ");
+    /// let readed = hasher
+    ///     .update_reader(&mut Cursor::new("world"))
+    ///     .expect("Unexpected error reading from a cursor");
+    /// assert_eq!(readed, 5);
+    /// let result = format!("{}", hasher.finalize());
+    /// assert_eq!(result, "5eb63bbbe01eeed093cb22bb8f5acdc3");
+    /// ```
+    pub fn update_reader<R: Read + ?Sized>(&mut self, input: &mut R) -> Result<u64, Md5Error> {
+        self.update_reader_with_buffer(input, &mut [0; DEFAULT_READ_BUFFER_SIZE])
+    }
+
+    #[cfg(feature = "std")]
+    /// Process all the data that can be readed from the `input` directly from its buffer,
+    /// returning the number of bytes readed.
+    ///
+    /// Reads interrupted by a signal are retried.
+    ///
+    /// # Errors
+    ///
+    /// Works like [Md5Hasher::update_reader].
+    pub fn update_buf_read<R: BufRead + ?Sized>(&mut self, input: &mut R) -> Result<u64, Md5Error> {
+        let mut total = 0;
+        loop {
+            let buffer = match input.fill_buf() {
+                Ok(buffer) => buffer,
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(read_error(error, total)),
+            };
+            if buffer.is_empty() {
+                break;
+            }
+            let readed = buffer.len();
+            self.update(buffer);
+            input.consume(readed);
+            total += readed as u64;
+        }
+        Ok(total)
+    }
+
+    #[cfg(feature = "std")]
+    fn update_reader_with_buffer<R: Read + ?Sized>(
+        &mut self,
+        input: &mut R,
+        buffer: &mut [u8],
+    ) -> Result<u64, Md5Error> {
+        read_chunks(input, buffer, |data| self.update(data))
+    }
+
+    /// Creates an instance that continues the computation of the `hash` of a message of
+    /// `message_length` bytes, as if the message and its padding were already processed.
+    ///
+    /// This allows to compute the hash of a message extended with [Md5Hasher::glue_padding] and
+    /// any other data without knowing the original message, the [length extension attack].
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(feature = "alloc", doc = "```")]
+    #[cfg_attr(not(feature = "alloc"), doc = "```ignore")]
+    /// use ya_md5::Md5Hasher;
+    ///
+    /// let original = Md5Hasher::hash_str("secret:data");
+    /// let mut hasher = Md5Hasher::from_hash(&original, 11);
+    /// hasher.update(";admin=true");
+    /// let forged = hasher.finalize();
+    ///
+    /// let mut message = Vec::from("secret:data");
+    /// message.extend(Md5Hasher::glue_padding(11));
+    /// message.extend(";admin=true".as_bytes());
+    /// assert_eq!(forged, Md5Hasher::hash_slice(&message));
+    /// ```
+    ///
+    /// [length extension attack]: https://en.wikipedia.org/wiki/Length_extension_attack
+    pub fn from_hash(hash: &Hash, message_length: u64) -> Md5Hasher {
+        let (_, range) = padding::<HashComputeState>(message_length.wrapping_mul(8));
+        let padded_length = message_length.wrapping_add(range.len() as u64);
+        Md5Hasher {
+            processor: ChunkProcessor::from_parts(
+                HashComputeState::from_raw(hash.as_bytes()),
+                &[],
+                0,
+                padded_length.wrapping_mul(8),
+            ),
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Returns the padding appended to a message of `message_length` bytes before computing its
+    /// hash: the `0x80` byte, the zeros and the length in bits as a little endian 64 bits
+    /// integer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ya_md5::Md5Hasher;
+    ///
+    /// let padding = Md5Hasher::glue_padding(3);
+    /// assert_eq!(padding.len(), 61);
+    /// assert_eq!(padding[0], 0x80);
+    /// assert_eq!(&padding[53..], &[24, 0, 0, 0, 0, 0, 0, 0]);
+    /// ```
+    pub fn glue_padding(message_length: u64) -> Vec<u8> {
+        let (blocks, range) = padding::<HashComputeState>(message_length.wrapping_mul(8));
+        blocks.as_flattened()[range].to_vec()
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Exports the internal state of the instance, so the hashing can be resumed later with
+    /// [Md5Hasher::import_state], even in another process.
+    ///
+    /// The state is a compact, versioned blob of at most 99 bytes with a checksum of its content.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ya_md5::Md5Hasher;
+    ///
+    /// let mut hasher = Md5Hasher::default();
+    /// hasher.update("hello ");
+    /// let state = hasher.export_state();
+    ///
+    /// let mut hasher = Md5Hasher::import_state(&state).expect("Invalid hasher state");
+    /// hasher.update("world");
+    /// let result = format!("{}", hasher.finalize());
+    /// assert_eq!(result, "5eb63bbbe01eeed093cb22bb8f5acdc3");
+    /// ```
+    pub fn export_state(&self) -> Vec<u8> {
+        let (buffer, length) = saved_state::export(&self.processor);
+        buffer[..length].to_vec()
+    }
+
+    /// Creates an instance from a state exported with [Md5Hasher::export_state].
+    ///
+    /// # Errors
+    ///
+    /// If the `state` is corrupt, truncated or was not created by a compatible version of this
+    /// library an error is returned.
+    pub fn import_state(state: &[u8]) -> Result<Md5Hasher, Md5Error> {
+        Ok(Md5Hasher {
+            processor: saved_state::import(state)?,
+        })
+    }
+
     /// Computes the hash of the internal state of the instance, consuming the instance in the
     /// process.
     pub fn finalize(self) -> Hash {
         self.processor.finalize()
     }
+
+    /// Computes the hash of the data processed so far without consuming the instance, so more data
+    /// can be processed later.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ya_md5::Md5Hasher;
+    ///
+    /// let mut hasher = Md5Hasher::default();
+    /// hasher.update("hello");
+    /// assert_eq!(format!("{}", hasher.peek_finalize()), "5d41402abc4b2a76b9719d911017c592");
+    /// hasher.update(" world");
+    /// assert_eq!(format!("{}", hasher.finalize()), "5eb63bbbe01eeed093cb22bb8f5acdc3");
+    /// ```
+    pub fn peek_finalize(&self) -> Hash {
+        self.processor.clone().finalize()
+    }
+
+    /// Computes the hash of the internal state of the instance and resets it to its initial state,
+    /// so it can be reused to hash other data without allocating again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ya_md5::Md5Hasher;
+    ///
+    /// let mut hasher = Md5Hasher::default();
+    /// for (data, expected) in [
+    ///     ("hello", "5d41402abc4b2a76b9719d911017c592"),
+    ///     ("world", "7d793037a0760186574b0282f2f435e7"),
+    /// ] {
+    ///     hasher.update(data);
+    ///     assert_eq!(format!("{}", hasher.finalize_reset()), expected);
+    /// }
+    /// ```
+    pub fn finalize_reset(&mut self) -> Hash {
+        self.processor.finalize_reset()
+    }
+
+    /// Discards the data processed so far, returning the instance to its initial state.
+    pub fn reset(&mut self) {
+        self.processor.reset();
+    }
+
+    /// Computes the hash of the internal state of the instance, consuming the instance in the
+    /// process, and compares it in constant time with the `expected` one.
+    ///
+    /// # Errors
+    ///
+    /// If the hashes are different a [Md5Error::DigestMismatch] with both of them is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ya_md5::{Hash, Md5Hasher};
+    ///
+    /// let expected: Hash = "5eb63bbbe01eeed093cb22bb8f5acdc3".parse().expect("Invalid digest");
+    /// let mut hasher = Md5Hasher::default();
+    /// hasher.update("hello world");
+    /// assert!(hasher.verify(&expected).is_ok());
+    /// ```
+    pub fn verify(self, expected: &Hash) -> Result<(), Md5Error> {
+        let computed = self.finalize();
+        if computed.ct_eq(expected) {
+            Ok(())
+        } else {
+            Err(Md5Error::DigestMismatch {
+                expected: *expected,
+                computed,
+            })
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+/// Processes every byte written as if passed to [Md5Hasher::update], never failing.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Cursor;
+/// use ya_md5::Md5Hasher;
+///
+/// let mut hasher = Md5Hasher::default();
+/// std::io::copy(&mut Cursor::new("hello world"), &mut hasher).expect("Unexpected error");
+/// let result = format!("{}", hasher.finalize());
+/// assert_eq!(result, "5eb63bbbe01eeed093cb22bb8f5acdc3");
+/// ```
+impl Write for Md5Hasher {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> std::io::Result<usize> {
+        let mut written = 0;
+        for buf in bufs {
+            self.update(&buf[..]);
+            written += buf.len();
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
 }
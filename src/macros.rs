@@ -0,0 +1,44 @@
+/// Computes the hash of a string literal at compile time.
+///
+/// # Examples
+///
+/// ```
+/// use ya_md5::{md5, Hash, Md5Hasher};
+///
+/// const NAMESPACE: Hash = md5!("cache:v1");
+/// assert_eq!(NAMESPACE, Md5Hasher::hash_str("cache:v1"));
+/// assert_eq!(format!("{}", md5!("hello world")), "5eb63bbbe01eeed093cb22bb8f5acdc3");
+/// ```
+#[macro_export]
+macro_rules! md5 {
+    ($data:literal) => {
+        const { $crate::Md5Hasher::hash_const(<str>::as_bytes($data)) }
+    };
+}
+
+/// Parses a hexadecimal digest literal at compile time, failing to compile if it's not valid.
+///
+/// # Examples
+///
+/// ```
+/// use ya_md5::{hash, Md5Hasher};
+///
+/// assert_eq!(Md5Hasher::hash_str("abc"), hash!("900150983cd24fb0d6963f7d28e17f72"));
+/// ```
+///
+/// ```compile_fail
+/// use ya_md5::hash;
+///
+/// let invalid = hash!("900150983cd24fb0d6963f7d28e17f7z");
+/// ```
+#[macro_export]
+macro_rules! hash {
+    ($hex:literal) => {
+        const {
+            match $crate::Hash::from_hex($hex) {
+                Some(hash) => hash,
+                None => panic!(concat!("Invalid hex digest: ", $hex)),
+            }
+        }
+    };
+}
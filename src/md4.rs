@@ -0,0 +1,289 @@
+use crate::chunk::{Chunk, CHUNK_SIZE_BYTES};
+use crate::chunk_processor::ChunkProcessor;
+use crate::compression_function::{CompressionFunction, Endianness};
+use crate::hash::Hash;
+use crate::hash_compute_state::{
+    aux_fun_f, aux_fun_h, HashComputeState, BLOCK_SIZE_WORDS, INITIAL_WORD_A, INITIAL_WORD_B,
+    INITIAL_WORD_C, INITIAL_WORD_D,
+};
+
+#[cfg(feature = "std")]
+use crate::md5_error::Md5Error;
+#[cfg(feature = "std")]
+use std::io::Read;
+
+// Constants added in the second and third rounds, the square roots of 2 and 3.
+const ROUND_2_CONSTANT: u32 = 0x5a827999;
+const ROUND_3_CONSTANT: u32 = 0x6ed9eba1;
+
+// The first and third rounds use the `F` and `H` functions of MD5, the second one the majority
+// of the bits instead of its `G`.
+const fn aux_fun_g(x: u32, y: u32, z: u32) -> u32 {
+    (x & y) | (x & z) | (y & z)
+}
+
+macro_rules! Md4Step {
+    ($aux_fun:ident, $a:ident, $b:ident, $c:ident, $d:ident, $x:expr, $s:expr, $t:expr) => {
+        $a = $a
+            .wrapping_add($aux_fun($b, $c, $d))
+            .wrapping_add($x)
+            .wrapping_add($t)
+            .rotate_left($s)
+    };
+}
+
+/// The four words of the MD4 state, which starts with the same values of MD5.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Md4State {
+    a: u32,
+    b: u32,
+    c: u32,
+    d: u32,
+}
+
+impl Default for Md4State {
+    fn default() -> Self {
+        Md4State {
+            a: INITIAL_WORD_A,
+            b: INITIAL_WORD_B,
+            c: INITIAL_WORD_C,
+            d: INITIAL_WORD_D,
+        }
+    }
+}
+
+impl CompressionFunction for Md4State {
+    type Block = Chunk;
+    type Output = Hash;
+    const BLOCK_SIZE: usize = CHUNK_SIZE_BYTES;
+    const EMPTY_BLOCK: Chunk = [0; CHUNK_SIZE_BYTES];
+    const LENGTH_ENDIANNESS: Endianness = Endianness::Little;
+
+    // The three rounds of RFC 1320, each one taking the 16 words of the block in its own order.
+    fn compress(self, chunk: &Chunk) -> Self {
+        let mut block = [0_u32; BLOCK_SIZE_WORDS];
+        for (item, bytes) in block.iter_mut().zip(chunk.chunks_exact(4)) {
+            *item = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+        let Md4State {
+            mut a,
+            mut b,
+            mut c,
+            mut d,
+        } = self;
+        // Round 1
+        Md4Step!(aux_fun_f, a, b, c, d, block[0], 3, 0);
+        Md4Step!(aux_fun_f, d, a, b, c, block[1], 7, 0);
+        Md4Step!(aux_fun_f, c, d, a, b, block[2], 11, 0);
+        Md4Step!(aux_fun_f, b, c, d, a, block[3], 19, 0);
+        Md4Step!(aux_fun_f, a, b, c, d, block[4], 3, 0);
+        Md4Step!(aux_fun_f, d, a, b, c, block[5], 7, 0);
+        Md4Step!(aux_fun_f, c, d, a, b, block[6], 11, 0);
+        Md4Step!(aux_fun_f, b, c, d, a, block[7], 19, 0);
+        Md4Step!(aux_fun_f, a, b, c, d, block[8], 3, 0);
+        Md4Step!(aux_fun_f, d, a, b, c, block[9], 7, 0);
+        Md4Step!(aux_fun_f, c, d, a, b, block[10], 11, 0);
+        Md4Step!(aux_fun_f, b, c, d, a, block[11], 19, 0);
+        Md4Step!(aux_fun_f, a, b, c, d, block[12], 3, 0);
+        Md4Step!(aux_fun_f, d, a, b, c, block[13], 7, 0);
+        Md4Step!(aux_fun_f, c, d, a, b, block[14], 11, 0);
+        Md4Step!(aux_fun_f, b, c, d, a, block[15], 19, 0);
+        // Round 2
+        Md4Step!(aux_fun_g, a, b, c, d, block[0], 3, ROUND_2_CONSTANT);
+        Md4Step!(aux_fun_g, d, a, b, c, block[4], 5, ROUND_2_CONSTANT);
+        Md4Step!(aux_fun_g, c, d, a, b, block[8], 9, ROUND_2_CONSTANT);
+        Md4Step!(aux_fun_g, b, c, d, a, block[12], 13, ROUND_2_CONSTANT);
+        Md4Step!(aux_fun_g, a, b, c, d, block[1], 3, ROUND_2_CONSTANT);
+        Md4Step!(aux_fun_g, d, a, b, c, block[5], 5, ROUND_2_CONSTANT);
+        Md4Step!(aux_fun_g, c, d, a, b, block[9], 9, ROUND_2_CONSTANT);
+        Md4Step!(aux_fun_g, b, c, d, a, block[13], 13, ROUND_2_CONSTANT);
+        Md4Step!(aux_fun_g, a, b, c, d, block[2], 3, ROUND_2_CONSTANT);
+        Md4Step!(aux_fun_g, d, a, b, c, block[6], 5, ROUND_2_CONSTANT);
+        Md4Step!(aux_fun_g, c, d, a, b, block[10], 9, ROUND_2_CONSTANT);
+        Md4Step!(aux_fun_g, b, c, d, a, block[14], 13, ROUND_2_CONSTANT);
+        Md4Step!(aux_fun_g, a, b, c, d, block[3], 3, ROUND_2_CONSTANT);
+        Md4Step!(aux_fun_g, d, a, b, c, block[7], 5, ROUND_2_CONSTANT);
+        Md4Step!(aux_fun_g, c, d, a, b, block[11], 9, ROUND_2_CONSTANT);
+        Md4Step!(aux_fun_g, b, c, d, a, block[15], 13, ROUND_2_CONSTANT);
+        // Round 3
+        Md4Step!(aux_fun_h, a, b, c, d, block[0], 3, ROUND_3_CONSTANT);
+        Md4Step!(aux_fun_h, d, a, b, c, block[8], 9, ROUND_3_CONSTANT);
+        Md4Step!(aux_fun_h, c, d, a, b, block[4], 11, ROUND_3_CONSTANT);
+        Md4Step!(aux_fun_h, b, c, d, a, block[12], 15, ROUND_3_CONSTANT);
+        Md4Step!(aux_fun_h, a, b, c, d, block[2], 3, ROUND_3_CONSTANT);
+        Md4Step!(aux_fun_h, d, a, b, c, block[10], 9, ROUND_3_CONSTANT);
+        Md4Step!(aux_fun_h, c, d, a, b, block[6], 11, ROUND_3_CONSTANT);
+        Md4Step!(aux_fun_h, b, c, d, a, block[14], 15, ROUND_3_CONSTANT);
+        Md4Step!(aux_fun_h, a, b, c, d, block[1], 3, ROUND_3_CONSTANT);
+        Md4Step!(aux_fun_h, d, a, b, c, block[9], 9, ROUND_3_CONSTANT);
+        Md4Step!(aux_fun_h, c, d, a, b, block[5], 11, ROUND_3_CONSTANT);
+        Md4Step!(aux_fun_h, b, c, d, a, block[13], 15, ROUND_3_CONSTANT);
+        Md4Step!(aux_fun_h, a, b, c, d, block[3], 3, ROUND_3_CONSTANT);
+        Md4Step!(aux_fun_h, d, a, b, c, block[11], 9, ROUND_3_CONSTANT);
+        Md4Step!(aux_fun_h, c, d, a, b, block[7], 11, ROUND_3_CONSTANT);
+        Md4Step!(aux_fun_h, b, c, d, a, block[15], 15, ROUND_3_CONSTANT);
+        Md4State {
+            a: self.a.wrapping_add(a),
+            b: self.b.wrapping_add(b),
+            c: self.c.wrapping_add(c),
+            d: self.d.wrapping_add(d),
+        }
+    }
+
+    fn output(self) -> Hash {
+        Hash::from(HashComputeState::from_words([self.a, self.b, self.c, self.d]).to_raw())
+    }
+}
+
+/// A hasher that computes the MD4 hash defined in [RFC 1320], with the same API of
+/// [Md5Hasher](crate::Md5Hasher).
+///
+/// MD4 is broken and must not be used for security; it's provided to interoperate with legacy
+/// protocols and formats that still require it, like NTLM and eD2k.
+///
+/// # Examples
+///
+/// ```
+/// use ya_md5::Md4Hasher;
+///
+/// let mut hasher = Md4Hasher::default();
+/// hasher.update("message ");
+/// hasher.update("digest");
+/// let result = format!("{}", hasher.finalize());
+/// assert_eq!(result, "d9130a8164549fe818874806e1c7014b");
+/// ```
+///
+/// [RFC 1320]: https://www.rfc-editor.org/rfc/rfc1320
+#[derive(Default, Clone)]
+pub struct Md4Hasher {
+    processor: ChunkProcessor<Md4State>,
+}
+
+impl Md4Hasher {
+    #[cfg(feature = "std")]
+    /// Computes and returns the hash of the data that can be readed from the `input`.
+    ///
+    /// # Errors
+    ///
+    /// Works like [Md5Hasher::hash](crate::Md5Hasher::hash).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use ya_md5::Md4Hasher;
+    ///
+    /// let hash = Md4Hasher::hash(&mut Cursor::new("abc".as_bytes()))
+    ///     .expect("Unexpected error reading from a cursor");
+    /// let result = format!("{}", hash);
+    /// assert_eq!(result, "a448017aaf21d8525fc10ae87aa6729d");
+    /// ```
+    pub fn hash<R: Read + ?Sized>(input: &mut R) -> Result<Hash, Md5Error> {
+        let mut hasher = Self::default();
+        hasher.update_reader(input)?;
+        Ok(hasher.finalize())
+    }
+
+    /// Computes and returns the hash of the data in the slice.
+    pub fn hash_slice(data: &[u8]) -> Hash {
+        let mut hasher = Self::default();
+        hasher.update(data);
+        hasher.finalize()
+    }
+
+    /// Computes and returns the hash of the data in the string slice.
+    pub fn hash_str(data: &str) -> Hash {
+        Self::hash_slice(data.as_bytes())
+    }
+
+    /// Process the `data` as part of the message.
+    pub fn update(&mut self, data: impl AsRef<[u8]>) {
+        self.processor.update(data);
+    }
+
+    #[cfg(feature = "std")]
+    /// Process all the data that can be readed from the `input` as part of the message, returning
+    /// the number of bytes readed.
+    ///
+    /// # Errors
+    ///
+    /// Works like [Md5Hasher::update_reader](crate::Md5Hasher::update_reader), including
+    /// resuming after an error.
+    pub fn update_reader<R: Read + ?Sized>(&mut self, input: &mut R) -> Result<u64, Md5Error> {
+        self.processor.update_reader(input)
+    }
+
+    /// Computes the hash of the message, consuming the instance in the process.
+    pub fn finalize(self) -> Hash {
+        self.processor.finalize()
+    }
+
+    /// Computes the hash of the message and resets the instance to its initial state, so it can
+    /// be reused to hash other data.
+    pub fn finalize_reset(&mut self) -> Hash {
+        self.processor.finalize_reset()
+    }
+
+    /// Discards the data processed so far, returning the instance to its initial state.
+    pub fn reset(&mut self) {
+        self.processor.reset();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+    #[cfg(feature = "std")]
+    use std::io::Cursor;
+
+    #[ctor::ctor]
+    fn init() {
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    // Test suite of RFC 1320, appendix A.5.
+    #[rstest]
+    #[case("", "31d6cfe0d16ae931b73c59d7e0c089c0")]
+    #[case("a", "bde52cb31de33e46245e05fbdbd6fb24")]
+    #[case("abc", "a448017aaf21d8525fc10ae87aa6729d")]
+    #[case("message digest", "d9130a8164549fe818874806e1c7014b")]
+    #[case("abcdefghijklmnopqrstuvwxyz", "d79e1c308aa5bbcdeea8ed63df412da9")]
+    #[case(
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+        "043f8582f241db351ce627e153e7f0e4"
+    )]
+    #[case(
+        "12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+        "e33b4ddc9c38f2199c3e7b164fcc0536"
+    )]
+    fn test_rfc_suite(#[case] data: &str, #[case] expected: &str) {
+        assert_eq!(format!("{}", Md4Hasher::hash_str(data)), expected);
+        let mut hasher = Md4Hasher::default();
+        for byte in data.as_bytes() {
+            hasher.update([*byte]);
+        }
+        assert_eq!(format!("{}", hasher.finalize()), expected);
+    }
+
+    #[cfg(feature = "std")]
+    #[rstest]
+    fn test_hash_reader() {
+        let hash = Md4Hasher::hash(&mut Cursor::new("message digest")).unwrap();
+        assert_eq!(format!("{}", hash), "d9130a8164549fe818874806e1c7014b");
+    }
+
+    #[rstest]
+    fn test_finalize_reset() {
+        let mut hasher = Md4Hasher::default();
+        hasher.update("abc");
+        assert_eq!(hasher.finalize_reset(), Md4Hasher::hash_str("abc"));
+        hasher.update("a");
+        hasher.reset();
+        hasher.update("message digest");
+        assert_eq!(
+            format!("{}", hasher.finalize()),
+            "d9130a8164549fe818874806e1c7014b"
+        );
+    }
+}
@@ -1,11 +1,23 @@
+use crate::encoding::Encoding;
+use crate::hash::Hash;
+
 use thiserror::Error;
 
 /// The error of a [Md5Hasher](crate::Md5Hasher) operation.
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum Md5Error {
     /// Error while doing [read](std::io::Read::read) from an input.
-    #[error("Error reading input: {0}")]
-    ReadError(std::io::Error),
+    ///
+    /// Reads interrupted by a signal are retried instead of returning this error.
+    #[cfg(feature = "std")]
+    #[error("Error reading input after {bytes_read} bytes: {source}")]
+    ReadError {
+        /// The error returned by the input.
+        source: std::io::Error,
+        /// The number of bytes readed and hashed before the error.
+        bytes_read: u64,
+    },
     /// Other I/O errors.
     /// While the [Md5Hasher](crate::Md5Hasher) don't use this error, it allows to create ergonomic
     /// functions for hashing a [std::io::Read] object.
@@ -27,6 +39,90 @@ pub enum Md5Error {
     /// }
     ///
     /// ```
+    #[cfg(feature = "std")]
     #[error("Unexpected I/O error: {0}")]
     IOError(#[from] std::io::Error),
+    /// The text of a digest doesn't have the length of any supported [Encoding].
+    #[error("Invalid digest length: {length}")]
+    InvalidDigestLength {
+        /// The length in bytes of the text.
+        length: usize,
+    },
+    /// The text of a digest contains a character that doesn't belong to the alphabet of the
+    /// [Encoding].
+    #[error("Invalid character {character:?} at position {position} of the {encoding} digest")]
+    InvalidDigestCharacter {
+        /// The byte offset of the character in the text.
+        position: usize,
+        /// The invalid character.
+        character: char,
+        /// The encoding used to decode the text.
+        encoding: Encoding,
+    },
+    /// The padding of the text of a digest contains a character other than `=`.
+    #[error("Invalid padding at position {position} of the {encoding} digest")]
+    InvalidDigestPadding {
+        /// The byte offset of the character in the text.
+        position: usize,
+        /// The encoding used to decode the text.
+        encoding: Encoding,
+    },
+    /// The last symbol of the text of a digest encodes trailing bits that are not zero.
+    #[error("Non-zero trailing bits at position {position} of the {encoding} digest")]
+    NonCanonicalDigest {
+        /// The byte offset of the symbol in the text.
+        position: usize,
+        /// The encoding used to decode the text.
+        encoding: Encoding,
+    },
+    /// The computed digest is not the expected one, returned by
+    /// [Md5Hasher::verify](crate::Md5Hasher::verify).
+    #[error("Digest mismatch: expected {expected}, computed {computed}")]
+    DigestMismatch {
+        /// The digest the data was expected to have.
+        expected: Hash,
+        /// The digest computed from the data.
+        computed: Hash,
+    },
+    /// The computed authentication code is not the expected one, returned by
+    /// [HmacMd5::verify](crate::HmacMd5::verify).
+    ///
+    /// Neither of the codes is included, so they don't end up in logs.
+    #[error("Authentication code mismatch")]
+    TagMismatch,
+    /// The expected authentication code is too short or too long, see
+    /// [HmacMd5::verify_truncated](crate::HmacMd5::verify_truncated).
+    #[error("Invalid authentication code length: {length}")]
+    InvalidTagLength {
+        /// The length in bytes of the expected code.
+        length: usize,
+    },
+    /// The saved state of a hasher doesn't have the expected length, see
+    /// [Md5Hasher::import_state](crate::Md5Hasher::import_state).
+    #[error("Invalid hasher state length: {length}, expected {expected}")]
+    InvalidStateLength {
+        /// The length in bytes of the saved state.
+        length: usize,
+        /// The length in bytes expected from the content of the saved state.
+        expected: usize,
+    },
+    /// The data is not a saved state of a hasher, see
+    /// [Md5Hasher::import_state](crate::Md5Hasher::import_state).
+    #[error("Invalid hasher state header")]
+    InvalidStateHeader,
+    /// The saved state of a hasher was created by an unsupported version of the library, see
+    /// [Md5Hasher::import_state](crate::Md5Hasher::import_state).
+    #[error("Unsupported hasher state version: {version}")]
+    UnsupportedStateVersion {
+        /// The version of the saved state.
+        version: u8,
+    },
+    /// The checksum of the saved state of a hasher doesn't match its content, see
+    /// [Md5Hasher::import_state](crate::Md5Hasher::import_state).
+    #[error("Hasher state checksum mismatch")]
+    StateChecksumMismatch,
+    /// The saved state of a hasher has a valid checksum but describes a state that the hasher
+    /// cannot reach, see [Md5Hasher::import_state](crate::Md5Hasher::import_state).
+    #[error("Inconsistent hasher state")]
+    InconsistentState,
 }
@@ -0,0 +1,337 @@
+//! Multi-buffer hashing: computes the hash of several independent messages at once, processing
+//! a chunk of each of them in lockstep in the lanes of the SIMD registers.
+//!
+//! The compression function is written for arrays of `N` lanes so the compiler vectorizes it,
+//! and is compiled once for each instruction set, selected at runtime:
+//!
+//! - AVX-512: 16 lanes.
+//! - AVX2: 8 lanes.
+//! - SSE2: 4 lanes.
+//! - Any other CPU: 4 lanes, with whatever SIMD the target enables by default.
+
+use crate::chunk::CHUNK_SIZE_BYTES;
+use crate::chunk_processor::{pad_block, write_length};
+use crate::compression_function::Endianness;
+use crate::conversions::u8_to_u32;
+use crate::hash::Hash;
+use crate::hash_compute_state::{
+    aux_fun_f, aux_fun_g, aux_fun_h, aux_fun_i, HashComputeState, BLOCK_SIZE_WORDS, SINE_TABLE,
+};
+use crate::Md5Hasher;
+
+const ROUND_SHIFTS: [[u32; 4]; 4] = [
+    [7, 12, 17, 22],
+    [5, 9, 14, 20],
+    [4, 11, 16, 23],
+    [6, 10, 15, 21],
+];
+
+// The state of every lane, one array per word.
+type LaneState<const N: usize> = [[u32; N]; 4];
+// The block of every lane, one array per word of the block.
+type LaneBlock<const N: usize> = [[u32; N]; BLOCK_SIZE_WORDS];
+type Compress<const N: usize> = unsafe fn(&mut LaneState<N>, &LaneBlock<N>);
+
+// Index of the word of the block used by each step.
+const fn word_index(step: usize) -> usize {
+    match step / 16 {
+        0 => step,
+        1 => (5 * step + 1) % 16,
+        2 => (3 * step + 5) % 16,
+        _ => (7 * step) % 16,
+    }
+}
+
+// The round and the auxiliary function are generic so each round is compiled with its own
+// function inlined and the indices of its steps known at compile time.
+#[inline(always)]
+fn round<const N: usize, const ROUND: usize>(
+    words: &mut LaneState<N>,
+    block: &LaneBlock<N>,
+    aux_fun: impl Fn(u32, u32, u32) -> u32,
+) {
+    for index in 0..16 {
+        let step = ROUND * 16 + index;
+        let x = &block[word_index(step)];
+        let shift = ROUND_SHIFTS[ROUND][index % 4];
+        // The roles of the words rotate every step (ABCD -> DABC) instead of moving the values.
+        let a = (4 - index % 4) % 4;
+        let (b, c, d) = ((a + 1) % 4, (a + 2) % 4, (a + 3) % 4);
+        for lane in 0..N {
+            let (b, c, d) = (words[b][lane], words[c][lane], words[d][lane]);
+            words[a][lane] = words[a][lane]
+                .wrapping_add(aux_fun(b, c, d))
+                .wrapping_add(x[lane])
+                .wrapping_add(SINE_TABLE[step])
+                .rotate_left(shift)
+                .wrapping_add(b);
+        }
+    }
+}
+
+#[inline(always)]
+fn compress_lanes<const N: usize>(state: &mut LaneState<N>, block: &LaneBlock<N>) {
+    let mut words = *state;
+    round::<N, 0>(&mut words, block, aux_fun_f);
+    round::<N, 1>(&mut words, block, aux_fun_g);
+    round::<N, 2>(&mut words, block, aux_fun_h);
+    round::<N, 3>(&mut words, block, aux_fun_i);
+    for (word, result) in state.iter_mut().zip(words.iter()) {
+        for lane in 0..N {
+            word[lane] = word[lane].wrapping_add(result[lane]);
+        }
+    }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx512f")]
+unsafe fn compress_avx512(state: &mut LaneState<16>, block: &LaneBlock<16>) {
+    compress_lanes(state, block)
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2")]
+unsafe fn compress_avx2(state: &mut LaneState<8>, block: &LaneBlock<8>) {
+    compress_lanes(state, block)
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "sse2")]
+unsafe fn compress_sse2(state: &mut LaneState<4>, block: &LaneBlock<4>) {
+    compress_lanes(state, block)
+}
+
+fn compress_portable(state: &mut LaneState<4>, block: &LaneBlock<4>) {
+    compress_lanes(state, block)
+}
+
+// A message being hashed in a lane.
+struct Lane<'a> {
+    index: usize,
+    chunks: std::slice::ChunksExact<'a, u8>,
+    // The remainder of the message followed by its padding, one or two chunks.
+    tail: [u8; CHUNK_SIZE_BYTES * 2],
+    tail_length: usize,
+    tail_offset: usize,
+}
+
+impl<'a> Lane<'a> {
+    fn new(index: usize, data: &'a [u8]) -> Self {
+        let chunks = data.chunks_exact(CHUNK_SIZE_BYTES);
+        let remainder = chunks.remainder();
+        let mut tail = [0; CHUNK_SIZE_BYTES * 2];
+        tail[..remainder.len()].copy_from_slice(remainder);
+        let tail_length = if pad_block(&mut tail[..CHUNK_SIZE_BYTES], remainder.len(), 0) {
+            CHUNK_SIZE_BYTES * 2
+        } else {
+            CHUNK_SIZE_BYTES
+        };
+        let size = (data.len() as u64).wrapping_mul(8);
+        write_length(&mut tail[..tail_length], size, Endianness::Little);
+        Lane {
+            index,
+            chunks,
+            tail,
+            tail_length,
+            tail_offset: 0,
+        }
+    }
+
+    // Returns the next chunk of the padded message, and if it's the last one.
+    fn next_chunk(&mut self) -> (&[u8], bool) {
+        match self.chunks.next() {
+            Some(chunk) => (chunk, false),
+            None => {
+                let offset = self.tail_offset;
+                self.tail_offset += CHUNK_SIZE_BYTES;
+                (
+                    &self.tail[offset..self.tail_offset],
+                    self.tail_offset == self.tail_length,
+                )
+            }
+        }
+    }
+}
+
+fn hash_lanes<const N: usize, T: AsRef<[u8]>>(inputs: &[T], compress: Compress<N>) -> Vec<Hash> {
+    let initial = HashComputeState::default().to_words();
+    let mut results = vec![Hash::from([0; 16]); inputs.len()];
+    let mut lanes: [Option<Lane>; N] = std::array::from_fn(|_| None);
+    let mut state: LaneState<N> = [[0; N]; 4];
+    let mut pending = inputs.iter().enumerate();
+    loop {
+        let mut block: LaneBlock<N> = [[0; N]; BLOCK_SIZE_WORDS];
+        let mut finished = [false; N];
+        let mut active = false;
+        for (lane_index, slot) in lanes.iter_mut().enumerate() {
+            if slot.is_none() {
+                if let Some((index, input)) = pending.next() {
+                    *slot = Some(Lane::new(index, input.as_ref()));
+                    for (word, value) in state.iter_mut().zip(initial.iter()) {
+                        word[lane_index] = *value;
+                    }
+                }
+            }
+            if let Some(lane) = slot {
+                let (chunk, last) = lane.next_chunk();
+                for (word, bytes) in block.iter_mut().zip(chunk.chunks_exact(4)) {
+                    word[lane_index] = u8_to_u32(&[bytes[0], bytes[1], bytes[2], bytes[3]]);
+                }
+                finished[lane_index] = last;
+                active = true;
+            }
+        }
+        if !active {
+            break;
+        }
+        // SAFETY: `compress` is only chosen when the CPU supports its instruction set.
+        unsafe { compress(&mut state, &block) };
+        for (lane_index, slot) in lanes.iter_mut().enumerate() {
+            if finished[lane_index] {
+                if let Some(lane) = slot.take() {
+                    let words = [
+                        state[0][lane_index],
+                        state[1][lane_index],
+                        state[2][lane_index],
+                        state[3][lane_index],
+                    ];
+                    results[lane.index] = Hash::from(HashComputeState::from_words(words).to_raw());
+                }
+            }
+        }
+    }
+    results
+}
+
+impl Md5Hasher {
+    /// Computes the hash of every slice in `inputs`, returning them in the same order.
+    ///
+    /// Several messages are hashed at once in the lanes of the SIMD registers, using the widest
+    /// instruction set supported by the CPU (AVX-512, AVX2 or SSE2), which is much faster than
+    /// hashing them one by one when there are many small or mid-sized messages. The results
+    /// are exactly the same as [Md5Hasher::hash_slice].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ya_md5::Md5Hasher;
+    ///
+    /// let hashes = Md5Hasher::hash_many_slices(&["hello".as_bytes(), "world".as_bytes()]);
+    /// assert_eq!(format!("{}", hashes[0]), "5d41402abc4b2a76b9719d911017c592");
+    /// assert_eq!(format!("{}", hashes[1]), "7d793037a0760186574b0282f2f435e7");
+    /// ```
+    pub fn hash_many_slices<T: AsRef<[u8]>>(inputs: &[T]) -> Vec<Hash> {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            if std::arch::is_x86_feature_detected!("avx512f") {
+                return hash_lanes(inputs, compress_avx512 as Compress<16>);
+            }
+            if std::arch::is_x86_feature_detected!("avx2") {
+                return hash_lanes(inputs, compress_avx2 as Compress<8>);
+            }
+            if std::arch::is_x86_feature_detected!("sse2") {
+                return hash_lanes(inputs, compress_sse2 as Compress<4>);
+            }
+        }
+        hash_lanes(inputs, compress_portable as Compress<4>)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    #[ctor::ctor]
+    fn init() {
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    // Messages of every length around the padding boundaries, so each lane ends at a different
+    // chunk and gets refilled.
+    fn messages() -> Vec<Vec<u8>> {
+        (0..200_usize)
+            .map(|length| (0..length).map(|byte| (byte * 7 + length) as u8).collect())
+            .collect()
+    }
+
+    fn check<const N: usize>(compress: Compress<N>) {
+        let messages = messages();
+        let hashes = hash_lanes(&messages, compress);
+        assert_eq!(hashes.len(), messages.len());
+        for (message, hash) in messages.iter().zip(hashes.iter()) {
+            assert_eq!(
+                *hash,
+                Md5Hasher::hash_slice(message),
+                "length {}",
+                message.len()
+            );
+        }
+    }
+
+    #[rstest]
+    fn test_portable() {
+        check(compress_portable as Compress<4>);
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[rstest]
+    #[case("sse2")]
+    #[case("avx2")]
+    #[case("avx512f")]
+    fn test_simd(#[case] feature: &str) {
+        match feature {
+            "sse2" if std::arch::is_x86_feature_detected!("sse2") => {
+                check(compress_sse2 as Compress<4>)
+            }
+            "avx2" if std::arch::is_x86_feature_detected!("avx2") => {
+                check(compress_avx2 as Compress<8>)
+            }
+            "avx512f" if std::arch::is_x86_feature_detected!("avx512f") => {
+                check(compress_avx512 as Compress<16>)
+            }
+            _ => log::info!("Skipping unsupported CPU feature {}", feature),
+        }
+    }
+
+    #[rstest]
+    #[case(&[])]
+    #[case(&["abc"])]
+    #[case(&["", "a", "abc", "message digest"])]
+    fn test_hash_many_slices(#[case] inputs: &[&str]) {
+        let hashes = Md5Hasher::hash_many_slices(inputs);
+        let expected: Vec<Hash> = inputs
+            .iter()
+            .map(|input| Md5Hasher::hash_str(input))
+            .collect();
+        assert_eq!(hashes, expected);
+    }
+
+    // Compares the throughput of hashing many messages at once and one by one, run it with
+    // `cargo test --release -- --ignored --nocapture`.
+    #[rstest]
+    #[ignore]
+    fn test_hash_many_slices_throughput() {
+        const MESSAGES: usize = 1 << 14;
+        const MESSAGE_SIZE_BYTES: usize = 1024;
+        let messages = vec![[0x5a_u8; MESSAGE_SIZE_BYTES]; MESSAGES];
+        let measure = |hash: &dyn Fn(&[[u8; MESSAGE_SIZE_BYTES]]) -> Vec<Hash>| {
+            let start = std::time::Instant::now();
+            std::hint::black_box(hash(std::hint::black_box(&messages)));
+            let elapsed = start.elapsed().as_secs_f64();
+            (MESSAGES * MESSAGE_SIZE_BYTES) as f64 / elapsed / (1024.0 * 1024.0)
+        };
+        let single = measure(&|messages| {
+            messages
+                .iter()
+                .map(|message| Md5Hasher::hash_slice(message))
+                .collect()
+        });
+        let multi = measure(&|messages| Md5Hasher::hash_many_slices(messages));
+        println!(
+            "One by one: {:.0} MiB/s, multi-buffer: {:.0} MiB/s",
+            single, multi
+        );
+        assert!(multi > single);
+    }
+}
@@ -0,0 +1,94 @@
+//! Parallel hashing of many inputs with [rayon].
+//!
+//! Requires the `rayon` feature.
+//!
+//! Unlike [Md5Hasher::hash_many], these functions run in the current rayon thread pool.
+
+use crate::hash::Hash;
+use crate::md5_error::Md5Error;
+use crate::Md5Hasher;
+
+use ::rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Computes the hash of every reader in `inputs` in parallel, returning the results in the same
+/// order as the `inputs`.
+///
+/// An error reading an input is returned as the result of that input without affecting the
+/// others.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Cursor;
+/// use ya_md5::rayon::hash_many;
+///
+/// let results = hash_many(vec![Cursor::new("hello"), Cursor::new("world")]);
+/// assert_eq!(format!("{}", results[0].as_ref().unwrap()), "5d41402abc4b2a76b9719d911017c592");
+/// assert_eq!(format!("{}", results[1].as_ref().unwrap()), "7d793037a0760186574b0282f2f435e7");
+/// ```
+pub fn hash_many<I>(inputs: I) -> Vec<Result<Hash, Md5Error>>
+where
+    I: IntoParallelIterator,
+    I::Item: Read,
+{
+    inputs
+        .into_par_iter()
+        .map(|mut input| Md5Hasher::hash(&mut input))
+        .collect()
+}
+
+/// Computes the hash of every file in `paths` in parallel, returning the results in the same
+/// order as the `paths`.
+///
+/// Works like [Md5Hasher::hash_files].
+pub fn hash_files<I>(paths: I) -> Vec<Result<Hash, Md5Error>>
+where
+    I: IntoParallelIterator,
+    I::Item: AsRef<Path>,
+{
+    paths
+        .into_par_iter()
+        .map(|path| Md5Hasher::hash(&mut File::open(path)?))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{hash_files, hash_many};
+    use crate::{Md5Error, Md5Hasher};
+    use rstest::rstest;
+    use std::io::Cursor;
+
+    #[ctor::ctor]
+    fn init() {
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    #[rstest]
+    fn test_hash_many() {
+        let inputs: Vec<String> = (0..200).map(|index| "a".repeat(index)).collect();
+        let results = hash_many(inputs.iter().map(Cursor::new).collect::<Vec<_>>());
+        assert_eq!(results.len(), inputs.len());
+        for (input, result) in inputs.iter().zip(results) {
+            assert_eq!(result.unwrap(), Md5Hasher::hash_str(input));
+        }
+    }
+
+    #[rstest]
+    fn test_hash_files() -> Result<(), Md5Error> {
+        let directory = tempfile::tempdir()?;
+        let present = directory.path().join("present.txt");
+        std::fs::write(&present, "abc")?;
+        let paths = vec![directory.path().join("missing.txt"), present];
+        let results = hash_files(&paths);
+        assert!(matches!(results[0], Err(Md5Error::IOError(_))));
+        assert_eq!(
+            results[1].as_ref().unwrap(),
+            &"900150983cd24fb0d6963f7d28e17f72"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,282 @@
+//! Serialization of the in-flight state of a [ChunkProcessor].
+//!
+//! The layout of the version 1 is, with every integer in little endian:
+//!
+//! | Offset  | Size | Content                                                  |
+//! |---------|------|----------------------------------------------------------|
+//! | 0       | 4    | Magic bytes `yaM5`                                       |
+//! | 4       | 1    | Version                                                  |
+//! | 5       | 16   | Words a, b, c and d of the [HashComputeState]            |
+//! | 21      | 8    | Size in bits of the chunks already processed             |
+//! | 29      | 1    | Length `n` of the buffer, lower than 64 unless partial   |
+//! | 30      | 1    | Bits of the last byte of the buffer used, 0 if all of it |
+//! | 31      | n    | Buffer                                                   |
+//! | 31 + n  | 4    | First 4 bytes of the MD5 hash of the previous bytes      |
+
+use crate::chunk::CHUNK_SIZE_BYTES;
+use crate::chunk_processor::{ChunkProcessor, CHUNK_LENGTH};
+#[cfg(any(feature = "alloc", test))]
+use crate::conversions::{u32_to_u8, u64_to_u8};
+use crate::conversions::{u8_to_u32, u8_to_u64};
+use crate::hash_compute_state::HashComputeState;
+use crate::md5_error::Md5Error;
+
+const MAGIC: &[u8; 4] = b"yaM5";
+const VERSION: u8 = 1;
+const WORDS_OFFSET: usize = MAGIC.len() + 1;
+const SIZE_OFFSET: usize = WORDS_OFFSET + 16;
+const BUFFER_LENGTH_OFFSET: usize = SIZE_OFFSET + 8;
+const PARTIAL_BITS_OFFSET: usize = BUFFER_LENGTH_OFFSET + 1;
+const BUFFER_OFFSET: usize = PARTIAL_BITS_OFFSET + 1;
+const CHECKSUM_SIZE_BYTES: usize = 4;
+const MIN_SIZE_BYTES: usize = BUFFER_OFFSET + CHECKSUM_SIZE_BYTES;
+
+fn checksum(data: &[u8]) -> [u8; CHECKSUM_SIZE_BYTES] {
+    let digest = {
+        let mut processor = ChunkProcessor::<HashComputeState>::default();
+        processor.update(data);
+        processor.finalize()
+    };
+    let mut result = [0; CHECKSUM_SIZE_BYTES];
+    result.copy_from_slice(&digest.as_bytes()[..CHECKSUM_SIZE_BYTES]);
+    result
+}
+
+#[cfg(any(feature = "alloc", test))]
+pub const MAX_SIZE_BYTES: usize = MIN_SIZE_BYTES + CHUNK_SIZE_BYTES;
+
+/// Writes the saved state of the `processor` at the start of the returned buffer, followed by its
+/// length.
+#[cfg(any(feature = "alloc", test))]
+pub fn export(processor: &ChunkProcessor) -> ([u8; MAX_SIZE_BYTES], usize) {
+    let buffer = processor.buffer();
+    let checksum_offset = BUFFER_OFFSET + buffer.len();
+    let mut result = [0; MAX_SIZE_BYTES];
+    result[..WORDS_OFFSET - 1].copy_from_slice(MAGIC);
+    result[WORDS_OFFSET - 1] = VERSION;
+    for (index, word) in processor.state().to_words().iter().enumerate() {
+        let offset = WORDS_OFFSET + index * 4;
+        result[offset..offset + 4].copy_from_slice(&u32_to_u8(word));
+    }
+    let mut size = [0; 8];
+    u64_to_u8(&processor.size(), &mut size);
+    result[SIZE_OFFSET..BUFFER_LENGTH_OFFSET].copy_from_slice(&size);
+    // The buffer is flushed as soon as it holds a whole chunk, so its length always fits.
+    result[BUFFER_LENGTH_OFFSET] = buffer.len() as u8;
+    result[PARTIAL_BITS_OFFSET] = processor.partial_bits();
+    result[BUFFER_OFFSET..checksum_offset].copy_from_slice(buffer);
+    let checksum = checksum(&result[..checksum_offset]);
+    result[checksum_offset..checksum_offset + CHECKSUM_SIZE_BYTES].copy_from_slice(&checksum);
+    (result, checksum_offset + CHECKSUM_SIZE_BYTES)
+}
+
+// Checks that the buffer can be reached by the processor: it fits in a chunk, only a partial last
+// byte can fill the whole chunk, and the bits of that byte after the partial ones are zero.
+fn is_consistent(buffer: &[u8], partial_bits: u8, size: u64) -> bool {
+    if !size.is_multiple_of(CHUNK_LENGTH) || partial_bits >= 8 || buffer.len() > CHUNK_SIZE_BYTES {
+        return false;
+    }
+    if partial_bits == 0 {
+        return buffer.len() < CHUNK_SIZE_BYTES;
+    }
+    matches!(buffer.last(), Some(last) if last & (0xff >> partial_bits) == 0)
+}
+
+pub fn import(data: &[u8]) -> Result<ChunkProcessor, Md5Error> {
+    if data.len() < MIN_SIZE_BYTES {
+        return Err(Md5Error::InvalidStateLength {
+            length: data.len(),
+            expected: MIN_SIZE_BYTES,
+        });
+    }
+    if &data[..MAGIC.len()] != MAGIC {
+        return Err(Md5Error::InvalidStateHeader);
+    }
+    let version = data[MAGIC.len()];
+    if version != VERSION {
+        return Err(Md5Error::UnsupportedStateVersion { version });
+    }
+    let buffer_length = data[BUFFER_LENGTH_OFFSET] as usize;
+    let expected = MIN_SIZE_BYTES + buffer_length;
+    if data.len() != expected {
+        return Err(Md5Error::InvalidStateLength {
+            length: data.len(),
+            expected,
+        });
+    }
+    let (content, stored_checksum) = data.split_at(data.len() - CHECKSUM_SIZE_BYTES);
+    if checksum(content) != stored_checksum {
+        return Err(Md5Error::StateChecksumMismatch);
+    }
+    let mut words = [0; 4];
+    for (index, word) in words.iter_mut().enumerate() {
+        let offset = WORDS_OFFSET + index * 4;
+        *word = u8_to_u32(&content[offset..offset + 4].try_into().unwrap());
+    }
+    let size = u8_to_u64(
+        &content[SIZE_OFFSET..BUFFER_LENGTH_OFFSET]
+            .try_into()
+            .unwrap(),
+    );
+    let partial_bits = content[PARTIAL_BITS_OFFSET];
+    let buffer = &content[BUFFER_OFFSET..];
+    if !is_consistent(buffer, partial_bits, size) {
+        return Err(Md5Error::InconsistentState);
+    }
+    Ok(ChunkProcessor::from_parts(
+        HashComputeState::from_words(words),
+        buffer,
+        partial_bits,
+        size,
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    #[ctor::ctor]
+    fn init() {
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    fn processor_with(data: &str) -> ChunkProcessor {
+        let mut processor = ChunkProcessor::default();
+        processor.update(data.as_bytes());
+        processor
+    }
+
+    fn export_vec(processor: &ChunkProcessor) -> Vec<u8> {
+        let (buffer, length) = export(processor);
+        buffer[..length].to_vec()
+    }
+
+    // Recomputes the checksum so the tests reach the checks after it.
+    fn with_checksum(mut data: Vec<u8>) -> Vec<u8> {
+        let length = data.len() - CHECKSUM_SIZE_BYTES;
+        let value = checksum(&data[..length]);
+        data[length..].copy_from_slice(&value);
+        data
+    }
+
+    #[rstest]
+    #[case("", "")]
+    #[case("abc", "")]
+    #[case("1234567890123456789012345678901234567890123456789012345678901234", "")]
+    #[case(
+        "12345678901234567890123456789012345678901234567890123456789012345",
+        "678"
+    )]
+    fn test_export_import(#[case] left: &str, #[case] right: &str) {
+        let blob = export_vec(&processor_with(left));
+        assert_eq!(blob.len(), MIN_SIZE_BYTES + left.len() % CHUNK_SIZE_BYTES);
+        assert_eq!(blob[MAGIC.len()], VERSION);
+        let mut processor = import(&blob).unwrap();
+        processor.update(right.as_bytes());
+        let expected = processor_with(&format!("{}{}", left, right)).finalize();
+        assert_eq!(processor.finalize(), expected);
+    }
+
+    #[rstest]
+    fn test_import_corrupt() {
+        let mut blob = export_vec(&processor_with("abc"));
+        blob[WORDS_OFFSET] ^= 1;
+        assert!(matches!(
+            import(&blob),
+            Err(Md5Error::StateChecksumMismatch)
+        ));
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(MIN_SIZE_BYTES - 1)]
+    fn test_import_truncated(#[case] length: usize) {
+        let blob = export_vec(&processor_with(""));
+        assert!(matches!(
+            import(&blob[..length]),
+            Err(Md5Error::InvalidStateLength {
+                expected: MIN_SIZE_BYTES,
+                ..
+            })
+        ));
+    }
+
+    #[rstest]
+    fn test_import_buffer_length_mismatch() {
+        let mut blob = export_vec(&processor_with("abc"));
+        blob.pop();
+        assert!(matches!(
+            import(&blob),
+            Err(Md5Error::InvalidStateLength { expected, .. }) if expected == MIN_SIZE_BYTES + 3
+        ));
+    }
+
+    #[rstest]
+    fn test_import_header() {
+        let mut blob = export_vec(&processor_with("abc"));
+        blob[0] = b'Y';
+        assert!(matches!(import(&blob), Err(Md5Error::InvalidStateHeader)));
+    }
+
+    #[rstest]
+    fn test_import_version() {
+        let mut blob = export_vec(&processor_with("abc"));
+        blob[MAGIC.len()] = 2;
+        assert!(matches!(
+            import(&blob),
+            Err(Md5Error::UnsupportedStateVersion { version: 2 })
+        ));
+    }
+
+    #[rstest]
+    #[case(1, 1)]
+    #[case(0, 1)]
+    #[case(0, 0)]
+    #[case(1, 0)]
+    #[case(1, 0b0100_0000)]
+    #[case(8, 0)]
+    fn test_export_import_bits(#[case] partial_bits: usize, #[case] last: u8) {
+        let data = [0xa5; CHUNK_SIZE_BYTES];
+        for length in [0, 3, CHUNK_SIZE_BYTES - 1] {
+            let bit_length = length * 8 + partial_bits;
+            let mut processor = ChunkProcessor::default();
+            processor.update_bits(&data, bit_length);
+            let mut imported = import(&export_vec(&processor)).unwrap();
+            processor.update_bits(&[last], 8);
+            imported.update_bits(&[last], 8);
+            assert_eq!(imported.finalize(), processor.finalize());
+        }
+    }
+
+    #[rstest]
+    #[case(SIZE_OFFSET, 1)]
+    #[case(PARTIAL_BITS_OFFSET, 8)]
+    #[case(PARTIAL_BITS_OFFSET, 1)]
+    fn test_import_inconsistent(#[case] offset: usize, #[case] value: u8) {
+        // The last byte of "abc" has bits set after the first one.
+        let mut blob = export_vec(&processor_with("abc"));
+        blob[offset] = value;
+        assert!(matches!(
+            import(&with_checksum(blob)),
+            Err(Md5Error::InconsistentState)
+        ));
+    }
+
+    #[rstest]
+    #[case(CHUNK_SIZE_BYTES, 0)]
+    #[case(CHUNK_SIZE_BYTES + 1, 1)]
+    #[case(100, 1)]
+    #[case(255, 7)]
+    fn test_import_inconsistent_buffer_length(#[case] length: usize, #[case] partial_bits: u8) {
+        let mut blob = export_vec(&processor_with(""));
+        blob.truncate(BUFFER_OFFSET);
+        blob[BUFFER_LENGTH_OFFSET] = length as u8;
+        blob[PARTIAL_BITS_OFFSET] = partial_bits;
+        blob.extend(vec![0; length + CHECKSUM_SIZE_BYTES]);
+        assert!(matches!(
+            import(&with_checksum(blob)),
+            Err(Md5Error::InconsistentState)
+        ));
+    }
+}
@@ -0,0 +1,192 @@
+use crate::hash::Hash;
+use crate::Md5Hasher;
+
+use alloc::vec::Vec;
+use core::fmt::Formatter;
+use serde::de::{Error, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+const DIGEST_SIZE_BYTES: usize = 16;
+
+/// Serializes the hash as lowercase hexadecimal text in human-readable formats and as its 16
+/// bytes otherwise.
+impl Serialize for Hash {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_bytes(self.as_bytes())
+        }
+    }
+}
+
+struct TextVisitor;
+
+impl Visitor<'_> for TextVisitor {
+    type Value = Hash;
+
+    fn expecting(&self, formatter: &mut Formatter) -> core::fmt::Result {
+        formatter.write_str("the text of a 128-bit digest")
+    }
+
+    fn visit_str<E: Error>(self, value: &str) -> Result<Hash, E> {
+        value.parse().map_err(E::custom)
+    }
+}
+
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Hash;
+
+    fn expecting(&self, formatter: &mut Formatter) -> core::fmt::Result {
+        formatter.write_str("the 16 bytes of a digest")
+    }
+
+    fn visit_bytes<E: Error>(self, value: &[u8]) -> Result<Hash, E> {
+        <[u8; DIGEST_SIZE_BYTES]>::try_from(value)
+            .map(Hash::from)
+            .map_err(|_| E::invalid_length(value.len(), &self))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Hash, A::Error> {
+        let mut value = [0_u8; DIGEST_SIZE_BYTES];
+        for (index, item) in value.iter_mut().enumerate() {
+            *item = seq
+                .next_element()?
+                .ok_or_else(|| A::Error::invalid_length(index, &self))?;
+        }
+        if seq.next_element::<u8>()?.is_some() {
+            return Err(A::Error::invalid_length(DIGEST_SIZE_BYTES + 1, &self));
+        }
+        Ok(Hash::from(value))
+    }
+}
+
+/// Deserializes the hash from any text accepted by its [FromStr](core::str::FromStr)
+/// implementation in human-readable formats and from exactly 16 bytes otherwise.
+impl<'de> Deserialize<'de> for Hash {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Hash, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(TextVisitor)
+        } else {
+            deserializer.deserialize_bytes(BytesVisitor)
+        }
+    }
+}
+
+/// Serializes the hasher as the bytes of [Md5Hasher::export_state].
+impl Serialize for Md5Hasher {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.export_state())
+    }
+}
+
+struct StateVisitor;
+
+impl<'de> Visitor<'de> for StateVisitor {
+    type Value = Md5Hasher;
+
+    fn expecting(&self, formatter: &mut Formatter) -> core::fmt::Result {
+        formatter.write_str("the saved state of a MD5 hasher")
+    }
+
+    fn visit_bytes<E: Error>(self, value: &[u8]) -> Result<Md5Hasher, E> {
+        Md5Hasher::import_state(value).map_err(E::custom)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Md5Hasher, A::Error> {
+        let mut value = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(item) = seq.next_element()? {
+            value.push(item);
+        }
+        self.visit_bytes(&value)
+    }
+}
+
+/// Deserializes the hasher from the bytes of [Md5Hasher::export_state], validating them with
+/// [Md5Hasher::import_state].
+impl<'de> Deserialize<'de> for Md5Hasher {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Md5Hasher, D::Error> {
+        deserializer.deserialize_bytes(StateVisitor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Hash, Md5Hasher};
+    use rstest::rstest;
+
+    #[ctor::ctor]
+    fn init() {
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    fn abc_hash() -> Hash {
+        "900150983cd24fb0d6963f7d28e17f72".parse().unwrap()
+    }
+
+    #[rstest]
+    fn test_json() {
+        let json = serde_json::to_string(&abc_hash()).unwrap();
+        assert_eq!(json, r#""900150983cd24fb0d6963f7d28e17f72""#);
+        let hash: Hash = serde_json::from_str(&json).unwrap();
+        assert_eq!(hash, abc_hash());
+    }
+
+    #[rstest]
+    #[case(r#""900150983cd24fb0d6963f7d28e17f7z""#)]
+    #[case(r#""900150983cd24fb0""#)]
+    #[case("12")]
+    fn test_json_invalid(#[case] json: &str) {
+        assert!(serde_json::from_str::<Hash>(json).is_err());
+    }
+
+    #[rstest]
+    fn test_message_pack() {
+        let bytes = rmp_serde::to_vec(&abc_hash()).unwrap();
+        // bin 8 marker, length and the digest.
+        assert_eq!(bytes.len(), 2 + 16);
+        assert_eq!(&bytes[2..], abc_hash().as_bytes());
+        let hash: Hash = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(hash, abc_hash());
+    }
+
+    #[rstest]
+    #[case(rmp_serde::to_vec(&RawBytes(&[0; 15])).unwrap())]
+    #[case(rmp_serde::to_vec(&RawBytes(&[0; 17])).unwrap())]
+    fn test_message_pack_invalid(#[case] bytes: Vec<u8>) {
+        assert!(rmp_serde::from_slice::<Hash>(&bytes).is_err());
+    }
+
+    struct RawBytes<'a>(&'a [u8]);
+
+    impl serde::Serialize for RawBytes<'_> {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(self.0)
+        }
+    }
+
+    #[rstest]
+    fn test_hasher() {
+        let mut hasher = Md5Hasher::default();
+        hasher.update("ab");
+        let json = serde_json::to_string(&hasher).unwrap();
+        let bytes = rmp_serde::to_vec(&hasher).unwrap();
+        for mut hasher in [
+            serde_json::from_str::<Md5Hasher>(&json).unwrap(),
+            rmp_serde::from_slice::<Md5Hasher>(&bytes).unwrap(),
+        ] {
+            hasher.update("c");
+            assert_eq!(hasher.finalize(), abc_hash());
+        }
+    }
+
+    #[rstest]
+    fn test_hasher_invalid() {
+        let mut state = Md5Hasher::default().export_state();
+        state[8] ^= 1;
+        let bytes = rmp_serde::to_vec(&RawBytes(&state)).unwrap();
+        assert!(rmp_serde::from_slice::<Md5Hasher>(&bytes).is_err());
+    }
+}
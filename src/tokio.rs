@@ -0,0 +1,326 @@
+//! Asynchronous hashing of [tokio] I/O objects.
+//!
+//! Requires the `tokio` feature.
+
+use crate::hash::Hash;
+use crate::md5_error::Md5Error;
+use crate::{Md5Hasher, DEFAULT_READ_BUFFER_SIZE};
+
+use ::tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, ReadBuf};
+use std::io::{ErrorKind, IoSlice};
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+impl Md5Hasher {
+    /// Computes and returns the hash of the data that can be readed from the asynchronous
+    /// `input`.
+    ///
+    /// Reads interrupted by a signal are retried.
+    ///
+    /// # Errors
+    ///
+    /// Works like [Md5Hasher::hash].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ya_md5::Md5Hasher;
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let hash = Md5Hasher::hash_async(&mut "hello world".as_bytes())
+    ///     .await
+    ///     .expect("Unexpected error reading from a slice");
+    /// let result = format!("{}", hash);
+    /// assert_eq!(result, "5eb63bbbe01eeed093cb22bb8f5acdc3");
+    /// # });
+    /// ```
+    pub async fn hash_async<R: AsyncRead + Unpin + ?Sized>(
+        input: &mut R,
+    ) -> Result<Hash, Md5Error> {
+        let mut hasher = Self::default();
+        hasher.update_async_reader(input).await?;
+        Ok(hasher.finalize())
+    }
+
+    /// Process all the data that can be readed from the asynchronous `input`, returning the
+    /// number of bytes readed.
+    ///
+    /// # Errors
+    ///
+    /// Works like [Md5Hasher::update_reader].
+    pub async fn update_async_reader<R: AsyncRead + Unpin + ?Sized>(
+        &mut self,
+        input: &mut R,
+    ) -> Result<u64, Md5Error> {
+        let mut buffer = vec![0; DEFAULT_READ_BUFFER_SIZE];
+        let mut total = 0;
+        loop {
+            let readed = match input.read(&mut buffer).await {
+                Ok(readed) => readed,
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(source) => {
+                    return Err(Md5Error::ReadError {
+                        source,
+                        bytes_read: total,
+                    })
+                }
+            };
+            if readed == 0 {
+                break;
+            }
+            self.update(&buffer[..readed]);
+            total += readed as u64;
+        }
+        Ok(total)
+    }
+}
+
+/// An [AsyncRead] adapter that computes the MD5 hash of every byte read through it.
+///
+/// The asynchronous version of [crate::HashingReader].
+///
+/// # Examples
+///
+/// ```
+/// use tokio::io::AsyncReadExt;
+/// use ya_md5::tokio::HashingReader;
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let mut reader = HashingReader::new("hello world".as_bytes());
+/// let mut content = String::new();
+/// reader.read_to_string(&mut content).await.expect("Unexpected error reading from a slice");
+/// let (_, hash, length) = reader.into_parts();
+/// assert_eq!(content, "hello world");
+/// assert_eq!(format!("{}", hash), "5eb63bbbe01eeed093cb22bb8f5acdc3");
+/// assert_eq!(length, 11);
+/// # });
+/// ```
+pub struct HashingReader<R> {
+    inner: R,
+    hasher: Md5Hasher,
+    length: u64,
+}
+
+impl<R: AsyncRead + Unpin> HashingReader<R> {
+    /// Creates an adapter that reads from `inner`.
+    pub fn new(inner: R) -> Self {
+        HashingReader {
+            inner,
+            hasher: Md5Hasher::default(),
+            length: 0,
+        }
+    }
+
+    /// Returns a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns the number of bytes read so far.
+    pub fn bytes_read(&self) -> u64 {
+        self.length
+    }
+
+    /// Consumes the adapter, returning the inner reader, the hash of the bytes read and the
+    /// number of them.
+    pub fn into_parts(self) -> (R, Hash, u64) {
+        (self.inner, self.hasher.finalize(), self.length)
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for HashingReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        let filled = buf.filled().len();
+        ready!(Pin::new(&mut this.inner).poll_read(cx, buf))?;
+        let data = &buf.filled()[filled..];
+        this.hasher.update(data);
+        this.length += data.len() as u64;
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// An [AsyncWrite] adapter that computes the MD5 hash of every byte written through it.
+///
+/// The asynchronous version of [crate::HashingWriter], only the bytes accepted by the inner
+/// writer are hashed.
+///
+/// # Examples
+///
+/// ```
+/// use tokio::io::AsyncWriteExt;
+/// use ya_md5::tokio::HashingWriter;
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let mut writer = HashingWriter::new(Vec::new());
+/// writer.write_all(b"hello world").await.expect("Unexpected error writing to a vector");
+/// let (content, hash, length) = writer.into_parts();
+/// assert_eq!(content, b"hello world");
+/// assert_eq!(format!("{}", hash), "5eb63bbbe01eeed093cb22bb8f5acdc3");
+/// assert_eq!(length, 11);
+/// # });
+/// ```
+pub struct HashingWriter<W> {
+    inner: W,
+    hasher: Md5Hasher,
+    length: u64,
+}
+
+impl<W: AsyncWrite + Unpin> HashingWriter<W> {
+    /// Creates an adapter that writes to `inner`.
+    pub fn new(inner: W) -> Self {
+        HashingWriter {
+            inner,
+            hasher: Md5Hasher::default(),
+            length: 0,
+        }
+    }
+
+    /// Returns a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns the number of bytes written so far.
+    pub fn bytes_written(&self) -> u64 {
+        self.length
+    }
+
+    /// Consumes the adapter, returning the inner writer, the hash of the bytes written and the
+    /// number of them.
+    ///
+    /// The inner writer is not flushed nor shut down.
+    pub fn into_parts(self) -> (W, Hash, u64) {
+        (self.inner, self.hasher.finalize(), self.length)
+    }
+
+    fn process(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+        self.length += data.len() as u64;
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for HashingWriter<W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        let written = ready!(Pin::new(&mut this.inner).poll_write(cx, buf))?;
+        this.process(&buf[..written]);
+        Poll::Ready(Ok(written))
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        let written = ready!(Pin::new(&mut this.inner).poll_write_vectored(cx, bufs))?;
+        let mut remaining = written;
+        for buf in bufs {
+            if remaining == 0 {
+                break;
+            }
+            let accepted = remaining.min(buf.len());
+            this.process(&buf[..accepted]);
+            remaining -= accepted;
+        }
+        Poll::Ready(Ok(written))
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{HashingReader, HashingWriter};
+    use crate::{Md5Error, Md5Hasher};
+    use ::tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use rstest::rstest;
+
+    #[ctor::ctor]
+    fn init() {
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    const DATA: &str =
+        "12345678901234567890123456789012345678901234567890123456789012345678901234567890";
+    const EXPECTED: &str = "57edf4a22be3c955ac49da2e2107b67a";
+
+    #[rstest]
+    #[::tokio::test]
+    async fn test_hash_async() {
+        let hash = Md5Hasher::hash_async(&mut DATA.as_bytes()).await.unwrap();
+        assert_eq!(hash, EXPECTED);
+    }
+
+    #[rstest]
+    #[::tokio::test]
+    async fn test_hash_async_error() {
+        let failing = ::tokio_test::io::Builder::new()
+            .read(DATA.as_bytes())
+            .read_error(std::io::ErrorKind::Other.into())
+            .build();
+        let mut reader = DATA.as_bytes().chain(failing);
+        let error = Md5Hasher::hash_async(&mut reader).await.unwrap_err();
+        assert!(matches!(
+            error,
+            Md5Error::ReadError {
+                bytes_read: 160,
+                ..
+            }
+        ));
+    }
+
+    #[rstest]
+    #[::tokio::test]
+    async fn test_hashing_reader() {
+        let mut reader = HashingReader::new(DATA.as_bytes());
+        let mut buffer = [0; 7];
+        let mut content = Vec::new();
+        loop {
+            let readed = reader.read(&mut buffer).await.unwrap();
+            if readed == 0 {
+                break;
+            }
+            content.extend_from_slice(&buffer[..readed]);
+        }
+        assert_eq!(reader.bytes_read(), DATA.len() as u64);
+        let (_, hash, length) = reader.into_parts();
+        assert_eq!(content, DATA.as_bytes());
+        assert_eq!(hash, EXPECTED);
+        assert_eq!(length, DATA.len() as u64);
+    }
+
+    #[rstest]
+    #[::tokio::test]
+    async fn test_hashing_writer() {
+        let (left, right) = DATA.split_at(30);
+        let mut writer = HashingWriter::new(Vec::new());
+        writer.write_all(left.as_bytes()).await.unwrap();
+        writer.write_all(right.as_bytes()).await.unwrap();
+        writer.flush().await.unwrap();
+        writer.shutdown().await.unwrap();
+        assert_eq!(writer.bytes_written(), DATA.len() as u64);
+        let (content, hash, _) = writer.into_parts();
+        assert_eq!(content, DATA.as_bytes());
+        assert_eq!(hash, EXPECTED);
+    }
+}
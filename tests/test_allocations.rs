@@ -0,0 +1,43 @@
+#![cfg(feature = "std")]
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use ya_md5::Md5Hasher;
+
+// Counts the allocations of the whole test binary, which only contains one test so no other
+// thread allocates while hashing.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[test]
+fn test_hashing_does_not_allocate() {
+    let data = [b'a'; 1000];
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+    let mut hasher = Md5Hasher::default();
+    hasher.update(&data[..10]);
+    hasher.update(&data[10..]);
+    let first = hasher.finalize_reset();
+    hasher.update(&data[..60]);
+    let second = hasher.finalize();
+    let slice = Md5Hasher::hash_slice(&data);
+    let reader = Md5Hasher::hash(&mut &data[..]).expect("Unexpected error reading from a slice");
+    let after = ALLOCATIONS.load(Ordering::SeqCst);
+    assert_eq!(after, before);
+    assert_eq!(first, slice);
+    assert_eq!(reader, slice);
+    assert_eq!(second, Md5Hasher::hash_slice(&data[..60]));
+}
@@ -1,3 +1,4 @@
+#![cfg(feature = "std")]
 #![allow(clippy::items_after_test_module)]
 
 use rstest::rstest;
@@ -5,8 +6,10 @@ use std::io;
 use std::io::Seek;
 use std::io::Write;
 use tempfile::tempfile;
+use ya_md5::Hash;
 use ya_md5::Md5Error;
 use ya_md5::Md5Hasher;
+use ya_md5::{hash, md5};
 
 #[ctor::ctor]
 fn init() {
@@ -46,6 +49,14 @@ fn test_hash_vec() {
     assert_eq!(result, "900150983cd24fb0d6963f7d28e17f72");
 }
 
+#[rstest]
+fn test_hash_const() {
+    const DIGEST: Hash = Md5Hasher::hash_const(b"abc");
+    assert_eq!(DIGEST, "900150983cd24fb0d6963f7d28e17f72");
+    assert_eq!(md5!("abc"), hash!("900150983cd24fb0d6963f7d28e17f72"));
+    assert_eq!(md5!(""), hash!("D41D8CD98F00B204E9800998ECF8427E"));
+}
+
 #[rstest]
 fn test_update_finalize() {
     let mut hasher = Md5Hasher::default();
@@ -54,3 +65,262 @@ fn test_update_finalize() {
     let result = format!("{}", digest);
     assert_eq!(result, "900150983cd24fb0d6963f7d28e17f72");
 }
+
+#[rstest]
+#[case("900150983cd24fb0d6963f7d28e17f72")]
+#[case("900150983CD24FB0D6963F7D28E17F72")]
+#[case("kAFQmDzST7DWlj99KOF/cg==")]
+#[case("kAFQmDzST7DWlj99KOF_cg")]
+#[case("SAAVBGB42JH3BVUWH56SRYL7OI======")]
+fn test_parse_hash(#[case] text: &str) -> Result<(), Md5Error> {
+    let digest: Hash = text.parse()?;
+    let result = format!("{}", digest);
+    assert_eq!(result, "900150983cd24fb0d6963f7d28e17f72");
+    let digest = Hash::try_from(text)?;
+    let result = format!("{}", digest);
+    assert_eq!(result, "900150983cd24fb0d6963f7d28e17f72");
+    Ok(())
+}
+
+#[rstest]
+fn test_parse_hash_error() {
+    let error = "900150983cd24fb0d6963f7d28e17f7z"
+        .parse::<Hash>()
+        .unwrap_err();
+    let result = format!("{}", error);
+    assert_eq!(
+        result,
+        "Invalid character 'z' at position 31 of the hex digest"
+    );
+}
+
+#[rstest]
+fn test_verify() -> Result<(), Md5Error> {
+    let mut hasher = Md5Hasher::default();
+    hasher.update("abc".as_bytes());
+    hasher.verify(&"900150983cd24fb0d6963f7d28e17f72".parse()?)
+}
+
+#[rstest]
+fn test_verify_mismatch() -> Result<(), Md5Error> {
+    let mut hasher = Md5Hasher::default();
+    hasher.update("abd".as_bytes());
+    let error = hasher
+        .verify(&"900150983cd24fb0d6963f7d28e17f72".parse()?)
+        .unwrap_err();
+    match error {
+        Md5Error::DigestMismatch { expected, computed } => {
+            assert_eq!(expected, "900150983cd24fb0d6963f7d28e17f72");
+            assert_eq!(computed, Md5Hasher::hash_str("abd"));
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+    Ok(())
+}
+
+#[rstest]
+#[case("", "")]
+#[case("secret", "&admin=true")]
+#[case("1234567890123456789012345678901234567890123456789012345", "1")]
+#[case("12345678901234567890123456789012345678901234567890123456", "12")]
+#[case(
+    "1234567890123456789012345678901234567890123456789012345678901234",
+    "1234567890123456789012345678901234567890123456789012345678901234567890"
+)]
+fn test_length_extension(#[case] message: &str, #[case] extension: &str) {
+    let original = Md5Hasher::hash_str(message);
+    let mut hasher = Md5Hasher::from_hash(&original, message.len() as u64);
+    hasher.update(extension.as_bytes());
+    let forged = hasher.finalize();
+    let mut expected = Vec::from(message.as_bytes());
+    expected.extend(Md5Hasher::glue_padding(message.len() as u64));
+    expected.extend(extension.as_bytes());
+    assert_eq!(forged, Md5Hasher::hash_slice(&expected));
+}
+
+#[rstest]
+#[case(&[0b0000_0000], 1, "1da635b1430f171c657206fd69fee0e8")]
+#[case(&[0b1000_0000], 1, "7e663710ae2348bf0deaca2c79311eae")]
+#[case(&[0b0110_1000], 5, "7aecc0f7268cc128fe17c3f439922034")]
+#[case(&[0b1111_1110], 7, "841e07f647563f66963a5f65ad1366b5")]
+#[case(b"abc", 24, "900150983cd24fb0d6963f7d28e17f72")]
+fn test_update_bits(#[case] data: &[u8], #[case] bit_length: usize, #[case] expected: &str) {
+    let mut hasher = Md5Hasher::default();
+    hasher.update_bits(data, bit_length);
+    assert_eq!(hasher.finalize(), expected);
+}
+
+#[rstest]
+fn test_update_bits_then_update() {
+    // The bits 0110 followed by the bytes of "bc" shifted 4 bits to the right.
+    let mut hasher = Md5Hasher::default();
+    hasher.update_bits(&[0x61], 4);
+    hasher.update("bc");
+    let mut expected = Md5Hasher::default();
+    expected.update_bits(&[0x66, 0x26, 0x30], 20);
+    assert_eq!(hasher.finalize(), expected.finalize());
+}
+
+#[rstest]
+#[should_panic]
+fn test_update_bits_too_long() {
+    Md5Hasher::default().update_bits(&[0], 9);
+}
+
+#[rstest]
+fn test_clone_peek_finalize() {
+    let mut hasher = Md5Hasher::default();
+    hasher.update("1234567890123456789012345678901234567890123456789012345678901234a".as_bytes());
+    let fork = hasher.clone();
+    assert_eq!(
+        hasher.peek_finalize(),
+        Md5Hasher::hash_str("1234567890123456789012345678901234567890123456789012345678901234a")
+    );
+    hasher.update("bc".as_bytes());
+    assert_eq!(
+        hasher.finalize(),
+        Md5Hasher::hash_str("1234567890123456789012345678901234567890123456789012345678901234abc")
+    );
+    assert_eq!(
+        fork.finalize(),
+        Md5Hasher::hash_str("1234567890123456789012345678901234567890123456789012345678901234a")
+    );
+}
+
+#[rstest]
+fn test_reset() {
+    let mut hasher = Md5Hasher::default();
+    hasher.update("xyz".as_bytes());
+    hasher.reset();
+    hasher.update("abc".as_bytes());
+    assert_eq!(hasher.finalize_reset(), "900150983cd24fb0d6963f7d28e17f72");
+    assert_eq!(hasher.finalize(), "d41d8cd98f00b204e9800998ecf8427e");
+}
+
+#[rstest]
+fn test_write() -> Result<(), Md5Error> {
+    let mut hasher = Md5Hasher::default();
+    write!(hasher, "a")?;
+    let written = hasher.write_vectored(&[
+        io::IoSlice::new("".as_bytes()),
+        io::IoSlice::new("b".as_bytes()),
+        io::IoSlice::new("c".as_bytes()),
+    ])?;
+    assert_eq!(written, 2);
+    hasher.flush()?;
+    assert_eq!(hasher.finalize(), "900150983cd24fb0d6963f7d28e17f72");
+    Ok(())
+}
+
+#[rstest]
+#[case(1)]
+#[case(63)]
+#[case(64)]
+#[case(65)]
+#[case(1024 * 1024)]
+fn test_hash_with_buffer_size(#[case] buffer_size: usize) -> Result<(), Md5Error> {
+    let mut file = tempfile()?;
+    file.write_all(&[b'a'; 1000])?;
+    file.seek(io::SeekFrom::Start(0))?;
+    let digest = Md5Hasher::hash_with_buffer_size(&mut file, buffer_size)?;
+    assert_eq!(digest, Md5Hasher::hash_slice(&[b'a'; 1000]));
+    Ok(())
+}
+
+#[rstest]
+#[case(1)]
+#[case(64)]
+#[case(100)]
+fn test_hash_buf_read(#[case] capacity: usize) -> Result<(), Md5Error> {
+    let mut reader = io::BufReader::with_capacity(capacity, &[b'a'; 1000][..]);
+    let digest = Md5Hasher::hash_buf_read(&mut reader)?;
+    assert_eq!(digest, Md5Hasher::hash_slice(&[b'a'; 1000]));
+    Ok(())
+}
+
+#[rstest]
+fn test_update_reader() -> Result<(), Md5Error> {
+    let mut hasher = Md5Hasher::default();
+    hasher.update("a".as_bytes());
+    assert_eq!(hasher.update_reader(&mut io::Cursor::new("b"))?, 1);
+    assert_eq!(hasher.update_buf_read(&mut io::Cursor::new(""))?, 0);
+    let input: &mut dyn io::Read = &mut io::Cursor::new("c");
+    assert_eq!(hasher.update_reader(input)?, 1);
+    assert_eq!(hasher.finalize(), "900150983cd24fb0d6963f7d28e17f72");
+    Ok(())
+}
+
+// A reader that returns each step in order: some data or an error of the given kind.
+struct ScriptedReader {
+    steps: Vec<Result<&'static str, io::ErrorKind>>,
+}
+
+impl io::Read for ScriptedReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.steps.is_empty() {
+            return Ok(0);
+        }
+        match self.steps.remove(0) {
+            Ok(data) => {
+                buf[..data.len()].copy_from_slice(data.as_bytes());
+                Ok(data.len())
+            }
+            Err(kind) => Err(io::Error::from(kind)),
+        }
+    }
+}
+
+#[rstest]
+fn test_hash_interrupted() -> Result<(), Md5Error> {
+    let mut reader = ScriptedReader {
+        steps: vec![
+            Err(io::ErrorKind::Interrupted),
+            Ok("a"),
+            Err(io::ErrorKind::Interrupted),
+            Ok("bc"),
+        ],
+    };
+    let digest = Md5Hasher::hash(&mut reader)?;
+    assert_eq!(digest, "900150983cd24fb0d6963f7d28e17f72");
+    Ok(())
+}
+
+#[rstest]
+fn test_hash_read_error() {
+    let mut reader = ScriptedReader {
+        steps: vec![Ok("a"), Ok("b"), Err(io::ErrorKind::BrokenPipe), Ok("c")],
+    };
+    let error = Md5Hasher::hash(&mut reader).unwrap_err();
+    assert!(matches!(
+        error,
+        Md5Error::ReadError { ref source, bytes_read: 2 } if source.kind() == io::ErrorKind::BrokenPipe
+    ));
+}
+
+#[rstest]
+fn test_update_reader_resume() -> Result<(), Md5Error> {
+    let mut reader = ScriptedReader {
+        steps: vec![Ok("ab"), Err(io::ErrorKind::TimedOut), Ok("c")],
+    };
+    let mut hasher = Md5Hasher::default();
+    let error = hasher.update_reader(&mut reader).unwrap_err();
+    assert!(matches!(error, Md5Error::ReadError { bytes_read: 2, .. }));
+    assert_eq!(hasher.update_reader(&mut reader)?, 1);
+    assert_eq!(hasher.finalize(), "900150983cd24fb0d6963f7d28e17f72");
+    Ok(())
+}
+
+#[rstest]
+fn test_update_buf_read_error() {
+    let mut reader = io::BufReader::new(ScriptedReader {
+        steps: vec![
+            Err(io::ErrorKind::Interrupted),
+            Ok("abc"),
+            Err(io::ErrorKind::Other),
+        ],
+    });
+    let mut hasher = Md5Hasher::default();
+    let error = hasher.update_buf_read(&mut reader).unwrap_err();
+    assert!(matches!(error, Md5Error::ReadError { bytes_read: 3, .. }));
+    assert_eq!(hasher.finalize(), "900150983cd24fb0d6963f7d28e17f72");
+}